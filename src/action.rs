@@ -1,4 +1,5 @@
 use core::num::NonZeroU8;
+use std::fmt;
 use std::str;
 
 #[derive(Debug, PartialEq)]
@@ -51,6 +52,30 @@ impl str::FromStr for Action {
     }
 }
 
+/// Writes the action in the same notation it's parsed from, so that "15+78" round-trips.
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Undo => write!(f, "u"),
+            Action::MoveCard {
+                from,
+                to,
+                count: None,
+            } => write!(f, "{}{}", from, to),
+            Action::MoveCard {
+                from,
+                to,
+                count: Some(count),
+            } if count.get() == u8::MAX => write!(f, "+{}{}", from, to),
+            Action::MoveCard {
+                from,
+                to,
+                count: Some(count),
+            } => write!(f, "{}+{}{}", count, from, to),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_action {
     use super::{Action, FromCoordinate, ToCoordinate};
@@ -88,6 +113,13 @@ mod test_action {
         );
     }
 
+    #[test]
+    fn display() {
+        for input in ["u", "1a", "d8", "3w", "+78", "15+78"] {
+            assert_eq!(input, input.parse::<Action>().unwrap().to_string());
+        }
+    }
+
     #[test]
     fn invalid() {
         assert_eq!(Err("Invalid input."), "0a\n".parse::<Action>());
@@ -114,6 +146,27 @@ pub enum ToCoordinate {
     Foundation(u8),
 }
 
+impl FromCoordinate {
+    pub fn as_char(&self) -> char {
+        ToCoordinate::from(*self).as_char()
+    }
+}
+
+impl From<FromCoordinate> for ToCoordinate {
+    fn from(from: FromCoordinate) -> Self {
+        match from {
+            FromCoordinate::Cascade(n) => ToCoordinate::Cascade(n),
+            FromCoordinate::Cell(n) => ToCoordinate::Cell(n),
+        }
+    }
+}
+
+impl fmt::Display for FromCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
 impl str::FromStr for FromCoordinate {
     type Err = &'static str;
 
@@ -127,6 +180,22 @@ impl str::FromStr for FromCoordinate {
     }
 }
 
+impl ToCoordinate {
+    pub fn as_char(&self) -> char {
+        match self {
+            ToCoordinate::Cascade(n) => (b'1' + n) as char,
+            ToCoordinate::Cell(n) => (b'a' + n) as char,
+            ToCoordinate::Foundation(n) => (b'w' + n) as char,
+        }
+    }
+}
+
+impl fmt::Display for ToCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
 impl str::FromStr for ToCoordinate {
     type Err = &'static str;
 
@@ -0,0 +1,182 @@
+use super::{data_dir, Game};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A copy of the game in progress, kept on disk so that it can be picked up again if the program
+/// is closed or dies part way through. The file is written in the same format as `Game::save`,
+/// and only while there's something worth resuming: it's removed once the game is won, or when a
+/// game that was saved to it is abandoned for a new one.
+#[derive(Debug)]
+pub struct Autosave {
+    path: PathBuf,
+    saved: Option<String>,
+}
+
+impl Autosave {
+    /// The autosave for one frontend, so that each keeps its own game. `None` if there's nowhere
+    /// to put it.
+    pub fn new(frontend: &str) -> Option<Self> {
+        data_dir().map(|dir| Self::at(dir.join(format!("autosave-{}", frontend))))
+    }
+
+    /// An autosave kept in a particular file.
+    pub fn at(path: PathBuf) -> Self {
+        Self { path, saved: None }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The game that was left unfinished last time, if there is one.
+    pub fn load(&mut self) -> io::Result<Option<Game>> {
+        let saved = match fs::read_to_string(&self.path) {
+            Ok(saved) => saved,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let game = Game::load(&saved).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.saved = Some(saved);
+        Ok(Some(game))
+    }
+
+    /// Keep the file up to date with the game, which should be called after every move. The file
+    /// is only written when the game has changed, and is removed when there's nothing left to
+    /// resume.
+    ///
+    /// If the file still holds a game left unfinished by an earlier session that was never
+    /// loaded, that game is returned once it's been replaced, so that it can be counted as
+    /// abandoned.
+    pub fn update(&mut self, game: &Game) -> io::Result<Option<Game>> {
+        if game.is_won() || game.history().len() == 1 {
+            return match self.saved {
+                Some(_) => self.remove().map(|()| None),
+                None => Ok(None),
+            };
+        }
+
+        let mut replaced = None;
+        let saved = game.save();
+        if self.saved.as_ref() != Some(&saved) {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            if self.saved.is_none() {
+                // A file that can't be read back couldn't have been resumed either.
+                replaced = fs::read_to_string(&self.path)
+                    .ok()
+                    .and_then(|earlier| Game::load(&earlier).ok());
+            }
+
+            // Write the new copy alongside the old one first, so that a crash part way through
+            // can't leave a half-written file behind.
+            let partial = self.path.with_extension("partial");
+            fs::write(&partial, &saved)?;
+            fs::rename(&partial, &self.path)?;
+            self.saved = Some(saved);
+        }
+
+        Ok(replaced)
+    }
+
+    /// Throw away the saved game.
+    pub fn remove(&mut self) -> io::Result<()> {
+        self.saved = None;
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Deck, Variant};
+    use std::{env, process};
+
+    /// A directory of its own for each test, which is removed again at the end.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            Self(env::temp_dir().join(format!("refcell-test-{}-{}", process::id(), name)))
+        }
+
+        fn autosave(&self) -> Autosave {
+            Autosave::at(self.0.join("autosave"))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn resume() {
+        let dir = TempDir::new("resume");
+        let mut autosave = dir.autosave();
+        let mut game = Game::deal_numbered(1, Variant::FreeCell);
+
+        autosave.update(&game).unwrap();
+        assert!(!autosave.path().exists());
+
+        game.action("3a".parse().unwrap()).unwrap();
+        autosave.update(&game).unwrap();
+        let resumed = autosave.load().unwrap().unwrap();
+        assert_eq!(game.moves(), resumed.moves());
+        assert_eq!(Some(1), resumed.number());
+
+        game.restart();
+        autosave.update(&game).unwrap();
+        assert!(!autosave.path().exists());
+        assert!(autosave.load().unwrap().is_none());
+    }
+
+    #[test]
+    fn leaves_other_games_alone() {
+        let dir = TempDir::new("other");
+        let mut autosave = dir.autosave();
+        let mut game = Game::deal(Deck::numbered(1));
+        game.action("3a".parse().unwrap()).unwrap();
+        autosave.update(&game).unwrap();
+
+        // Starting a new game doesn't throw away one that was saved by an earlier session, until
+        // a move is played in it, and then the earlier game is handed back.
+        let mut later = Autosave::at(autosave.path().to_path_buf());
+        let mut next = Game::deal(Deck::numbered(2));
+        assert!(later.update(&next).unwrap().is_none());
+        assert!(later.path().exists());
+
+        next.action("3a".parse().unwrap()).unwrap();
+        let replaced = later.update(&next).unwrap().unwrap();
+        assert_eq!(game.moves(), replaced.moves());
+        assert_eq!(next.moves(), later.load().unwrap().unwrap().moves());
+
+        // It's only handed back the once.
+        next.action("2b".parse().unwrap()).unwrap();
+        assert!(later.update(&next).unwrap().is_none());
+
+        later.remove().unwrap();
+        assert!(!autosave.path().exists());
+        later.remove().unwrap();
+    }
+
+    #[test]
+    fn invalid() {
+        let dir = TempDir::new("invalid");
+        let mut autosave = dir.autosave();
+        fs::create_dir_all(autosave.path().parent().unwrap()).unwrap();
+        fs::write(autosave.path(), "moves: 3a").unwrap();
+
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            autosave.load().unwrap_err().kind()
+        );
+        autosave.remove().unwrap();
+    }
+}
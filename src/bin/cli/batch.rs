@@ -0,0 +1,224 @@
+use super::{autoplay, Deal, Screen};
+use refcell::*;
+
+/// A deal and the moves to play on it, as read by `--batch`.
+///
+/// Moves are written as they're typed, separated by spaces or new lines, and anything after a
+/// `#` is a comment. A line can also choose the game with `deal: <number>`, `seed: <number>` or
+/// `variant: <name>`, instead of the command line options of the same names.
+#[derive(Debug, Default)]
+pub struct Script<'a> {
+    pub deal: Option<Deal>,
+    pub variant: Option<Variant>,
+    pub moves: Vec<(usize, &'a str)>,
+}
+
+impl<'a> Script<'a> {
+    pub fn parse(input: &'a str, language: Language) -> Result<Self, String> {
+        let mut script = Self::default();
+
+        for (number, line) in lines(input) {
+            let error = |message| language.format("Line {}: {}", &[&number, &language.tr(message)]);
+
+            match line.split_once(':') {
+                Some((key, value)) => {
+                    let value = value.trim();
+                    match key.trim() {
+                        "deal" if script.deal.is_some() => {
+                            return Err(error("The deal has already been chosen"))
+                        }
+                        "seed" if script.deal.is_some() => {
+                            return Err(error("The deal has already been chosen"))
+                        }
+                        "deal" => {
+                            let number = value.parse().map_err(|_| error("Invalid deal number"))?;
+                            script.deal = Some(Deal::Numbered(number));
+                        }
+                        "seed" => {
+                            let seed = value.parse().map_err(|_| error("Invalid seed"))?;
+                            script.deal = Some(Deal::Seeded(seed));
+                        }
+                        "variant" => script.variant = Some(value.parse().map_err(error)?),
+                        _ => return Err(error("Unknown setting")),
+                    }
+                }
+                None => script
+                    .moves
+                    .extend(line.split_whitespace().map(|text| (number, text))),
+            }
+        }
+
+        Ok(script)
+    }
+
+    /// Play every move, skipping any that can't be played, then print the final position along
+    /// with a summary of the moves that went wrong. Returns the exit code: success only if the
+    /// game was won.
+    pub fn run(&self, game: &mut Game, autoplay_on: bool, screen: Screen) -> i32 {
+        let language = screen.options.language;
+        let errors: Vec<String> = self
+            .moves
+            .iter()
+            .filter_map(|&(number, text)| {
+                match language
+                    .parse_action(text)
+                    .and_then(|action| game.action(action))
+                {
+                    Ok(()) => {
+                        if autoplay_on {
+                            autoplay(game);
+                        }
+                        None
+                    }
+                    Err(message) => Some(
+                        language
+                            .format("Line {}, {}: {}", &[&number, &text, &language.tr(message)]),
+                    ),
+                }
+            })
+            .collect();
+
+        println!("{}", screen.board(game.tableau()));
+
+        if !errors.is_empty() {
+            eprintln!(
+                "{}",
+                language.format(
+                    "{} of {} moves couldn't be played:",
+                    &[&errors.len(), &self.moves.len()],
+                )
+            );
+            for error in errors {
+                eprintln!("  {}", error);
+            }
+        }
+
+        if game.is_won() {
+            match game.moves().len() {
+                1 => println!("{}", language.tr("Won in 1 move.")),
+                moves => println!("{}", language.format("Won in {} moves.", &[&moves])),
+            }
+            0
+        } else {
+            let played: usize = game
+                .tableau()
+                .foundations
+                .iter()
+                .map(|foundation| foundation.cards().len())
+                .sum();
+            println!(
+                "{}",
+                language.format(
+                    "Not won: {} cards are still to be played.",
+                    &[&(52 - played)],
+                )
+            );
+            1
+        }
+    }
+}
+
+/// The numbered lines of a file of moves, with comments and blank lines left out.
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or_default().trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    const SCREEN: Screen = Screen {
+        options: DisplayOptions {
+            highlight: Highlight::Off,
+            palette: Palette::TwoColour,
+            colours: ColourSupport::Plain,
+            cards: CardStyle::Text,
+            language: Language::English,
+        },
+        describe: false,
+    };
+
+    fn parse(input: &str) -> Result<Script<'_>, String> {
+        Script::parse(input, Language::English)
+    }
+
+    #[test]
+    fn parse_settings() {
+        let script =
+            parse("# Deal 1\ndeal: 1\nvariant: bakers-game\n3a 2b # first\n\n6c\n").unwrap();
+        assert!(matches!(script.deal, Some(Deal::Numbered(1))));
+        assert_eq!(Some(Variant::BakersGame), script.variant);
+        assert_eq!(vec![(4, "3a"), (4, "2b"), (6, "6c")], script.moves);
+
+        let script = parse("seed: 42").unwrap();
+        assert!(matches!(script.deal, Some(Deal::Seeded(42))));
+        assert!(script.moves.is_empty());
+    }
+
+    #[test]
+    fn parse_errors() {
+        for (input, error) in [
+            (
+                "deal: 1\nseed: 2",
+                "Line 2: The deal has already been chosen",
+            ),
+            (
+                "3a\ndeal: 1\n\ndeal: 2",
+                "Line 4: The deal has already been chosen",
+            ),
+            ("deal: one", "Line 1: Invalid deal number"),
+            ("seed: -1", "Line 1: Invalid seed"),
+            ("variant: spider", "Line 1: Unknown variant."),
+            ("undo: off", "Line 1: Unknown setting"),
+        ] {
+            assert_eq!(Err(error.to_string()), parse(input).map(|_| ()));
+        }
+
+        assert_eq!(
+            Err("Ligne 1 : Réglage inconnu".to_string()),
+            Script::parse("undo: off", Language::French).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn run_not_won() {
+        // The second "3a" has nowhere to go, and "9z" isn't a move, so only the first is played.
+        let script = parse("3a 3a\n9z").unwrap();
+        let mut game = Game::deal(Deck::numbered(1));
+
+        assert_eq!(1, script.run(&mut game, false, SCREEN));
+        assert_eq!(1, game.moves().len());
+    }
+
+    #[test]
+    fn run_won() {
+        let mut game = Game::load(
+            "# RefCell saved game
+            variant: freecell
+            w: AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC
+            x: AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD
+            y: AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH
+            z: AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS
+            1: QS KC
+            2: KD
+            3: KH
+            4: KS
+            moves:",
+        )
+        .unwrap();
+
+        assert_eq!(0, parse("1a").unwrap().run(&mut game, false, SCREEN));
+        assert!(game.is_won());
+    }
+
+    #[test]
+    fn unreadable() {
+        let script = Path::new("/nonexistent/script");
+        assert_eq!(2, crate::batch(crate::Args::default(), script));
+    }
+}
@@ -0,0 +1,263 @@
+use refcell::{Action, Game, Language};
+
+/// Anything that can be typed at the prompt other than a move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    New,
+    Restart,
+    Undo,
+    Board,
+    Hint,
+    Moves,
+    History,
+    Save,
+    Highlight,
+    Palette,
+    Glyphs,
+    Score,
+    Splits,
+    Stats,
+    Help,
+    Quit,
+}
+
+/// Every command, with the names it can be typed as, what can follow it, and what it does, in
+/// the order they're listed by `help`.
+pub const COMMANDS: &[(&[&str], Command, &str, &str)] = &[
+    (
+        &["new", "n"],
+        Command::New,
+        "[deal]",
+        "Start a new game, with a random deal or a numbered one",
+    ),
+    (
+        &["restart", "r"],
+        Command::Restart,
+        "",
+        "Go back to the start of this deal",
+    ),
+    (&["undo", "u"], Command::Undo, "", "Take back the last move"),
+    (&["board", "b"], Command::Board, "", "Show the board again"),
+    (&["hint"], Command::Hint, "", "Suggest a move"),
+    (
+        &["moves", "m"],
+        Command::Moves,
+        "",
+        "List every move that can be played",
+    ),
+    (
+        &["history"],
+        Command::History,
+        "",
+        "List the moves played so far",
+    ),
+    (
+        &["save"],
+        Command::Save,
+        "<file>",
+        "Save the game, to carry on later with --load",
+    ),
+    (
+        &["highlight", "h"],
+        Command::Highlight,
+        "",
+        "Change which cards are highlighted",
+    ),
+    (
+        &["colours", "colors", "c"],
+        Command::Palette,
+        "",
+        "Switch between two and four suit colours",
+    ),
+    (
+        &["glyphs", "g"],
+        Command::Glyphs,
+        "",
+        "Switch between text and compact card glyphs",
+    ),
+    (
+        &["score"],
+        Command::Score,
+        "",
+        "Show the score for this game so far",
+    ),
+    (
+        &["splits", "time"],
+        Command::Splits,
+        "",
+        "Show the time taken, and when each split was reached",
+    ),
+    (
+        &["stats"],
+        Command::Stats,
+        "",
+        "Show your record over every game you've finished",
+    ),
+    (&["help", "?"], Command::Help, "", "Show this list"),
+    (&["quit", "exit", "q"], Command::Quit, "", "Stop playing"),
+];
+
+/// One line of input: either a command along with whatever was typed after it, or a move.
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    Command(Command, String),
+    Action(Action),
+}
+
+impl Input {
+    /// Read a line, with moves typed in the letters of `language`. Command names are the same in
+    /// every language.
+    pub fn parse(input: &str, language: Language) -> Result<Self, &'static str> {
+        let input = input.trim();
+        let (name, argument) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let name = name.to_lowercase();
+
+        match COMMANDS
+            .iter()
+            .find(|(names, _, _, _)| names.contains(&name.as_str()))
+        {
+            Some((_, command, _, _)) => Ok(Input::Command(*command, argument.trim().to_string())),
+            None => language.parse_action(input).map(Input::Action),
+        }
+    }
+}
+
+/// Everything that could complete a partly typed line: the moves that can be played right now,
+/// in each of the ways they can be written, and the names of the commands.
+pub fn completions(game: &Game, prefix: &str, language: Language) -> Vec<String> {
+    let moves = game
+        .legal_moves()
+        .into_iter()
+        .flat_map(|action| match action {
+            // A stack can be moved by giving its size, by "+" for as many cards as will go, or,
+            // onto a card, by the source and destination alone.
+            Action::MoveCard {
+                from,
+                to,
+                count: Some(_),
+            } => {
+                let pair = language.action_string(&Action::MoveCard {
+                    from,
+                    to,
+                    count: None,
+                });
+                vec![language.action_string(&action), format!("+{}", pair), pair]
+            }
+            action => vec![language.action_string(&action)],
+        });
+    let commands = COMMANDS.iter().map(|(names, _, _, _)| names[0].to_string());
+
+    // A stack move is offered under its source too, so that "1" finds "2+17" as well as "17".
+    let prefix = prefix.trim_start();
+    let mut completions: Vec<String> = moves
+        .chain(commands)
+        .filter(|completion| {
+            let pair = completion.split_once('+').map_or("", |(_, pair)| pair);
+            completion.starts_with(prefix) || pair.starts_with(prefix)
+        })
+        .collect();
+    completions.sort();
+    completions.dedup();
+    completions
+}
+
+/// Whether a game has moves that would be lost by starting another, so the player should be asked
+/// first.
+pub fn is_in_progress(game: &Game) -> bool {
+    game.history().len() > 1 && !game.is_won()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use refcell::{Deck, FromCoordinate, ToCoordinate};
+
+    fn command(input: &str) -> Input {
+        Input::parse(input, Language::English).unwrap()
+    }
+
+    #[test]
+    fn aliases() {
+        for (input, expected) in [
+            ("u", Command::Undo),
+            ("undo", Command::Undo),
+            ("b", Command::Board),
+            ("time", Command::Splits),
+            ("splits", Command::Splits),
+            ("colors", Command::Palette),
+            ("?", Command::Help),
+            ("exit", Command::Quit),
+            ("  QUIT  ", Command::Quit),
+        ] {
+            assert_eq!(Input::Command(expected, String::new()), command(input));
+        }
+    }
+
+    #[test]
+    fn names_are_unique() {
+        let mut names: Vec<_> = COMMANDS.iter().flat_map(|(names, ..)| *names).collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(count, names.len());
+    }
+
+    #[test]
+    fn arguments() {
+        assert_eq!(
+            Input::Command(Command::New, "11982".to_string()),
+            command("new 11982")
+        );
+        assert_eq!(
+            Input::Command(Command::New, "11982".to_string()),
+            command("n   11982 ")
+        );
+        assert_eq!(
+            Input::Command(Command::Save, "my game.txt".to_string()),
+            command("save my game.txt")
+        );
+    }
+
+    #[test]
+    fn moves() {
+        let action = || Action::MoveCard {
+            from: FromCoordinate::Cascade(2),
+            to: ToCoordinate::Cell(0),
+            count: None,
+        };
+        assert_eq!(Input::Action(action()), command("3a"));
+        assert_eq!(
+            Ok(Input::Action(action())),
+            Input::parse("3a", Language::French)
+        );
+        assert!(Input::parse("shuffle", Language::English).is_err());
+    }
+
+    #[test]
+    fn in_progress() {
+        let mut game = Game::deal(Deck::numbered(1));
+        assert!(!is_in_progress(&game));
+
+        game.action("3a".parse().unwrap()).unwrap();
+        assert!(is_in_progress(&game));
+
+        game.restart();
+        assert!(!is_in_progress(&game));
+
+        let won = Game::load(
+            "# RefCell saved game
+            variant: freecell
+            w: AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC
+            x: AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD
+            y: AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH
+            z: AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS
+            1: QS KC
+            2: KD
+            3: KH
+            4: KS
+            moves: 1a",
+        )
+        .unwrap();
+        assert!(!is_in_progress(&won));
+    }
+}
@@ -0,0 +1,450 @@
+use std::io::{self, IsTerminal, Write};
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::{clear, cursor};
+
+/// Reads lines from the terminal with the usual editing keys, recall of earlier lines with the
+/// arrow keys, and tab completion. When the input or output isn't a terminal, lines are read as
+/// they are, so that piped input keeps working.
+#[derive(Debug, Default)]
+pub struct Editor {
+    history: Vec<String>,
+}
+
+/// The line being edited, and where the cursor is in it, counted in characters.
+#[derive(Debug, Default)]
+struct Line {
+    chars: Vec<char>,
+    cursor: usize,
+}
+
+impl Line {
+    fn set(&mut self, text: &str) {
+        self.chars = text.chars().collect();
+        self.cursor = self.chars.len();
+    }
+
+    fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    fn insert(&mut self, c: char) {
+        self.chars.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    /// Delete everything between the cursor and `to`, which can be on either side of it.
+    fn delete_to(&mut self, to: usize) {
+        let (start, end) = (self.cursor.min(to), self.cursor.max(to));
+        self.chars.drain(start..end);
+        self.cursor = start;
+    }
+
+    /// Where the word before the cursor starts.
+    fn word_start(&self) -> usize {
+        let chars = &self.chars[..self.cursor];
+        let end = chars
+            .iter()
+            .rposition(|c| !c.is_whitespace())
+            .map_or(0, |i| i + 1);
+        chars[..end]
+            .iter()
+            .rposition(|c| c.is_whitespace())
+            .map_or(0, |i| i + 1)
+    }
+}
+
+/// What a key press did to the line being edited.
+#[derive(Debug, PartialEq)]
+enum Edit {
+    /// The line was entered, or the input was ended with `None`.
+    Done(Option<String>),
+
+    /// The line or the cursor changed, so it needs drawing again.
+    Changed,
+
+    /// Tab found nothing that could complete the line.
+    NoCompletions,
+
+    /// Tab found several ways to go on, to be listed above the line.
+    Completions(Vec<String>),
+
+    /// The key doesn't do anything here.
+    Ignored,
+}
+
+/// The state of one `read_line`: the line itself, and which of the earlier lines is showing.
+struct Session<'a> {
+    history: &'a mut Vec<String>,
+    line: Line,
+    recalled: usize,
+
+    /// What was typed before going back through the history, to come back to at the end of it.
+    draft: String,
+}
+
+impl<'a> Session<'a> {
+    fn new(history: &'a mut Vec<String>) -> Self {
+        Self {
+            recalled: history.len(),
+            history,
+            line: Line::default(),
+            draft: String::new(),
+        }
+    }
+
+    /// Apply a key to the line. A line that's entered is added to the history, unless it's blank
+    /// or the same as the one before.
+    fn press(&mut self, key: Key, complete: impl Fn(&str) -> Vec<String>) -> Edit {
+        let line = &mut self.line;
+
+        match key {
+            Key::Char('\n') | Key::Char('\r') => {
+                let text = line.text();
+                if !text.trim().is_empty() && self.history.last() != Some(&text) {
+                    self.history.push(text.clone());
+                }
+                return Edit::Done(Some(text));
+            }
+            Key::Ctrl('c') => return Edit::Done(None),
+            Key::Ctrl('d') if line.chars.is_empty() => return Edit::Done(None),
+            Key::Char('\t') if line.cursor == line.chars.len() => {
+                let text = line.text();
+                let candidates = complete(&text);
+                let common = common_prefix(&candidates);
+
+                if candidates.is_empty() {
+                    return Edit::NoCompletions;
+                } else if common.len() > text.len() {
+                    line.set(common);
+                } else {
+                    return Edit::Completions(candidates);
+                }
+            }
+            Key::Char('\t') => return Edit::Ignored,
+            Key::Char(c) => line.insert(c),
+            Key::Backspace | Key::Ctrl('h') if line.cursor > 0 => line.delete_to(line.cursor - 1),
+            Key::Delete | Key::Ctrl('d') if line.cursor < line.chars.len() => {
+                line.delete_to(line.cursor + 1)
+            }
+            Key::Left | Key::Ctrl('b') => line.cursor = line.cursor.saturating_sub(1),
+            Key::Right | Key::Ctrl('f') => line.cursor = (line.cursor + 1).min(line.chars.len()),
+            Key::Home | Key::Ctrl('a') => line.cursor = 0,
+            Key::End | Key::Ctrl('e') => line.cursor = line.chars.len(),
+            Key::Ctrl('u') => line.delete_to(0),
+            Key::Ctrl('k') => line.delete_to(line.chars.len()),
+            Key::Ctrl('w') => line.delete_to(line.word_start()),
+            Key::Up | Key::Ctrl('p') if self.recalled > 0 => {
+                if self.recalled == self.history.len() {
+                    self.draft = line.text();
+                }
+                self.recalled -= 1;
+                line.set(&self.history[self.recalled]);
+            }
+            Key::Down | Key::Ctrl('n') if self.recalled < self.history.len() => {
+                self.recalled += 1;
+                line.set(self.history.get(self.recalled).unwrap_or(&self.draft));
+            }
+            _ => return Edit::Ignored,
+        }
+
+        Edit::Changed
+    }
+}
+
+impl Editor {
+    /// Read the next line, or `None` at the end of the input. `complete` is given the text before
+    /// the cursor when Tab is pressed, and returns every line that could complete it.
+    pub fn read_line(
+        &mut self,
+        prompt: &str,
+        complete: impl Fn(&str) -> Vec<String>,
+    ) -> io::Result<Option<String>> {
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            let mut buffer = String::new();
+            return Ok(match io::stdin().read_line(&mut buffer)? {
+                0 => None,
+                _ => Some(buffer),
+            });
+        }
+
+        let mut terminal = io::stdout().into_raw_mode()?;
+        let mut session = Session::new(&mut self.history);
+
+        write!(terminal, "{}", prompt)?;
+        terminal.flush()?;
+
+        for key in io::stdin().keys() {
+            match session.press(key?, &complete) {
+                Edit::Done(line) => {
+                    write!(terminal, "\r\n")?;
+                    return Ok(line);
+                }
+                Edit::Changed => {}
+                Edit::NoCompletions => write!(terminal, "\x07")?,
+                Edit::Completions(candidates) => {
+                    write!(terminal, "\r\n{}\r\n", candidates.join("  "))?
+                }
+                Edit::Ignored => continue,
+            }
+
+            let line = &session.line;
+            write!(
+                terminal,
+                "\r{}{}{}",
+                clear::CurrentLine,
+                prompt,
+                line.text()
+            )?;
+            let after_cursor = line.chars.len() - line.cursor;
+            if after_cursor > 0 {
+                write!(terminal, "{}", cursor::Left(after_cursor as u16))?;
+            }
+            terminal.flush()?;
+        }
+
+        write!(terminal, "\r\n")?;
+        Ok(None)
+    }
+}
+
+/// The longest string that every candidate starts with.
+fn common_prefix(candidates: &[String]) -> &str {
+    let first = match candidates.first() {
+        Some(first) => first.as_str(),
+        None => return "",
+    };
+
+    let len = candidates
+        .iter()
+        .map(|candidate| {
+            first
+                .chars()
+                .zip(candidate.chars())
+                .take_while(|(a, b)| a == b)
+                .count()
+        })
+        .min()
+        .unwrap_or(0);
+
+    &first[..first
+        .char_indices()
+        .nth(len)
+        .map_or(first.len(), |(i, _)| i)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::completions;
+    use refcell::{Game, Language};
+
+    fn none(_: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Press each key in turn, returning what the last one did.
+    fn press(session: &mut Session, keys: &[Key]) -> Edit {
+        keys.iter()
+            .map(|&key| session.press(key, none))
+            .last()
+            .unwrap()
+    }
+
+    fn type_text(session: &mut Session, text: &str) {
+        for c in text.chars() {
+            session.press(Key::Char(c), none);
+        }
+    }
+
+    #[test]
+    fn editing_keys() {
+        let mut history = Vec::new();
+        let mut session = Session::new(&mut history);
+        type_text(&mut session, "new 11982");
+
+        press(&mut session, &[Key::Ctrl('a'), Key::Char('x')]);
+        assert_eq!("xnew 11982", session.line.text());
+        assert_eq!(1, session.line.cursor);
+
+        press(&mut session, &[Key::Ctrl('e'), Key::Backspace]);
+        assert_eq!("xnew 1198", session.line.text());
+
+        press(&mut session, &[Key::Ctrl('w')]);
+        assert_eq!("xnew ", session.line.text());
+
+        press(&mut session, &[Key::Left, Key::Left, Key::Ctrl('k')]);
+        assert_eq!("xne", session.line.text());
+
+        press(&mut session, &[Key::Home, Key::Delete, Key::End]);
+        assert_eq!("ne", session.line.text());
+        assert_eq!(2, session.line.cursor);
+
+        press(&mut session, &[Key::Left, Key::Ctrl('u')]);
+        assert_eq!("e", session.line.text());
+        assert_eq!(0, session.line.cursor);
+    }
+
+    #[test]
+    fn word_start() {
+        let mut line = Line::default();
+        line.set("save  my game  ");
+        assert_eq!(9, line.word_start());
+
+        line.cursor = 8;
+        assert_eq!(6, line.word_start());
+    }
+
+    #[test]
+    fn history() {
+        let mut history = vec!["3a".to_string(), "hint".to_string()];
+        let mut session = Session::new(&mut history);
+        type_text(&mut session, "un");
+
+        assert_eq!(Edit::Changed, press(&mut session, &[Key::Up]));
+        assert_eq!("hint", session.line.text());
+        press(&mut session, &[Key::Up]);
+        assert_eq!("3a", session.line.text());
+        assert_eq!(Edit::Ignored, press(&mut session, &[Key::Up]));
+
+        // Going forward past the newest line brings back what was being typed.
+        press(&mut session, &[Key::Down, Key::Down]);
+        assert_eq!("un", session.line.text());
+        assert_eq!(Edit::Ignored, press(&mut session, &[Key::Down]));
+
+        press(&mut session, &[Key::Up]);
+        assert_eq!(
+            Edit::Done(Some("hint".to_string())),
+            press(&mut session, &[Key::Char('\n')])
+        );
+
+        // Repeated and blank lines aren't added again.
+        let mut session = Session::new(&mut history);
+        press(&mut session, &[Key::Char(' '), Key::Char('\r')]);
+        let mut session = Session::new(&mut history);
+        type_text(&mut session, "board");
+        press(&mut session, &[Key::Char('\n')]);
+        assert_eq!(vec!["3a", "hint", "board"], history);
+    }
+
+    #[test]
+    fn end_of_input() {
+        let mut history = Vec::new();
+        let mut session = Session::new(&mut history);
+        assert_eq!(Edit::Done(None), press(&mut session, &[Key::Ctrl('d')]));
+
+        // With something typed, Ctrl-D deletes instead, like Delete.
+        type_text(&mut session, "3a");
+        assert_eq!(
+            Edit::Changed,
+            press(&mut session, &[Key::Home, Key::Ctrl('d')])
+        );
+        assert_eq!("a", session.line.text());
+        assert_eq!(
+            Edit::Ignored,
+            press(&mut session, &[Key::End, Key::Ctrl('d')])
+        );
+
+        assert_eq!(Edit::Done(None), press(&mut session, &[Key::Ctrl('c')]));
+    }
+
+    #[test]
+    fn common_prefixes() {
+        let strings = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!("", common_prefix(&[]));
+        assert_eq!("history", common_prefix(&strings(&["history"])));
+        assert_eq!("h", common_prefix(&strings(&["help", "hint", "history"])));
+        assert_eq!("hi", common_prefix(&strings(&["hint", "history"])));
+        assert_eq!("10♥", common_prefix(&strings(&["10♥a", "10♥b"])));
+    }
+
+    #[test]
+    fn tab() {
+        let commands = |prefix: &str| {
+            ["help", "highlight", "hint", "history"]
+                .into_iter()
+                .filter(|name| name.starts_with(prefix))
+                .map(str::to_string)
+                .collect()
+        };
+        let mut history = Vec::new();
+        let mut session = Session::new(&mut history);
+
+        session.press(Key::Char('h'), commands);
+        assert_eq!(
+            Edit::Completions(vec![
+                "help".to_string(),
+                "highlight".to_string(),
+                "hint".to_string(),
+                "history".to_string()
+            ]),
+            session.press(Key::Char('\t'), commands)
+        );
+
+        session.press(Key::Char('i'), commands);
+        session.press(Key::Char('s'), commands);
+        assert_eq!(Edit::Changed, session.press(Key::Char('\t'), commands));
+        assert_eq!("history", session.line.text());
+
+        session.press(Key::Char('x'), commands);
+        assert_eq!(
+            Edit::NoCompletions,
+            session.press(Key::Char('\t'), commands)
+        );
+
+        // Tab only completes at the end of the line.
+        session.press(Key::Left, commands);
+        assert_eq!(Edit::Ignored, session.press(Key::Char('\t'), commands));
+    }
+
+    #[test]
+    fn tab_lists_stack_moves() {
+        let game = Game::load(
+            "# RefCell saved game
+            variant: freecell
+            w: AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC
+            x: AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD
+            y: AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH
+            z: AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS
+            1: QD KC QH
+            2: KD
+            3: KH
+            4: QS
+            5: KS
+            moves:",
+        )
+        .unwrap();
+        let complete = |prefix: &str| completions(&game, prefix, Language::English);
+        let mut history = Vec::new();
+        let mut session = Session::new(&mut history);
+
+        session.press(Key::Char('1'), complete);
+        let listed = match session.press(Key::Char('\t'), complete) {
+            Edit::Completions(listed) => listed,
+            edit => panic!("expected a list of moves, not {:?}", edit),
+        };
+        for expected in ["1y", "15", "17", "2+17", "+17", "1a"] {
+            assert!(
+                listed.iter().any(|listed| listed == expected),
+                "{}",
+                expected
+            );
+        }
+        assert!(!listed.iter().any(|listed| listed == "4z"));
+        assert_eq!("1", session.line.text());
+
+        // The stack forms complete like any other move.
+        session.line.set("2+");
+        assert_eq!(Edit::Changed, session.press(Key::Char('\t'), complete));
+        assert_eq!("2+1", session.line.text());
+        assert_eq!(
+            Edit::Completions(vec![
+                "2+16".to_string(),
+                "2+17".to_string(),
+                "2+18".to_string()
+            ]),
+            session.press(Key::Char('\t'), complete)
+        );
+    }
+}
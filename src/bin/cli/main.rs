@@ -0,0 +1,657 @@
+use batch::Script;
+use command::{completions, is_in_progress, Command, Input, COMMANDS};
+use editor::Editor;
+use refcell::*;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::{env, process};
+
+mod batch;
+mod command;
+mod editor;
+
+const USAGE: &str = "\
+Usage: cli [options]
+
+Options:
+  --deal <number>   Play a numbered deal, the same as in Microsoft FreeCell
+  --seed <number>   Play a shuffled deal that's the same every time for a given seed
+  --variant <name>  Rules to play by: freecell (the default) or bakers-game
+  --undo <mode>     costly (the default) to take points off for each undo, free,
+                    off, or the number of undos allowed
+  --colour <mode>   auto (the default), plain, monochrome, 16, 256 or truecolour
+  --language <code> Language for messages and card names: en or fr (the default
+                    comes from LANG)
+  --describe        Describe the board in words instead of drawing it, and read
+                    out each move, for screen readers
+  --autoplay        Move cards to the foundations once nothing else can use them
+  --no-autoplay     Leave every move to the player (the default)
+  --load <file>     Carry on with a game saved with \"save <file>\"
+  --replay <file>   Play the moves listed in a file before handing over
+  --batch <file>    Play a script of moves without any input, then exit with
+                    success only if the game was won (\"-\" reads standard input)
+  -h, --help        Show this message
+  -V, --version     Show the version number
+";
+
+const INSTRUCTIONS: &str = "\
+Type the character for the source position followed by the destination position.
+For instance, to move from the third column to the first free cell, type \"3a\".
+Type \"help\" for the list of commands, such as \"hint\", \"undo\" and \"new\".";
+
+const MOVES_HELP: &str = "\
+Moves are typed as the source followed by the destination: 1-8 for the cascades,
+a-d for the free cells and w-z for the foundations. To move a stack to an empty
+cascade, put the number of cards and \"+\" in front, as in \"3+15\", or just \"+\" for
+as many as possible.
+";
+
+fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|message| {
+        eprint!("{}\n\n{}", message, Language::detect().tr(USAGE));
+        process::exit(2);
+    });
+    if let Some(path) = args.batch.clone() {
+        process::exit(batch(args, &path));
+    }
+
+    let mut game = args.game().unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        process::exit(1);
+    });
+    let mut screen = args.screen();
+    let language = screen.options.language;
+    let mut editor = Editor::default();
+
+    // Offer the game from last time, unless the command line says which game to play.
+    let mut autosave = Autosave::new("cli");
+    if !args.chooses_game() {
+        if let Some(resumed) = resume(&mut autosave, &mut editor, language) {
+            game = resumed;
+        }
+    }
+
+    let scoring = Scoring::load().unwrap_or_else(|e| {
+        let path = Scoring::config_path().unwrap_or_default();
+        let message = language.format(
+            "Couldn't read the scoring config {}: {}",
+            &[&path.display(), &e],
+        );
+        println!("{}", message);
+        Scoring::default()
+    });
+
+    // Whether this game has been won and added to the statistics.
+    let mut recorded = false;
+
+    if let Some(path) = &args.replay {
+        replay(&mut game, path, screen).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        });
+    }
+    if args.autoplay {
+        autoplay(&mut game);
+    }
+
+    println!("{}", screen.board(game.tableau()));
+    println!("{}", language.tr(INSTRUCTIONS));
+
+    // Stop at the end of the input, as well as when asked to.
+    while let Some(line) = read_line(&mut editor, |prefix| completions(&game, prefix, language)) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (command, argument) = match Input::parse(&line, language) {
+            Ok(Input::Command(command, argument)) => (command, argument),
+            Ok(Input::Action(action)) => {
+                play(&mut game, action, args.autoplay, screen);
+                if game.is_won() && !recorded {
+                    let moves = game.moves().len();
+                    let time = game.elapsed();
+                    let score = scoring.score(&game, time);
+                    println!(
+                        "{}",
+                        language.format("Time: {}  Score: {}", &[&format_time(time), &score])
+                    );
+                    let outcome = Outcome::Won { moves, time, score };
+                    record(game.undo_mode(), outcome, language);
+                    recorded = true;
+                }
+                keep(&mut autosave, &game, language);
+                continue;
+            }
+            Err(msg) => {
+                println!("{}", language.tr(msg));
+                continue;
+            }
+        };
+
+        match command {
+            Command::New => {
+                let variant = game.history()[0].variant;
+                let new_game = match argument.as_str() {
+                    "" => Game::deal_variant(Deck::shuffled(), variant),
+                    number => match number.parse() {
+                        Ok(number) => Game::deal_numbered(number, variant),
+                        Err(_) => {
+                            println!("{}", language.tr("The deal has to be a number."));
+                            continue;
+                        }
+                    },
+                };
+                let new_game = new_game.with_undo_mode(game.undo_mode());
+
+                if confirm_abandon(&game, &mut editor, language) {
+                    game = new_game;
+                    recorded = false;
+                    if args.autoplay {
+                        autoplay(&mut game);
+                    }
+                    println!("{}", screen.board(game.tableau()));
+                }
+            }
+            Command::Restart => {
+                if confirm_abandon(&game, &mut editor, language) {
+                    game.restart();
+                    recorded = false;
+                    println!("{}", screen.board(game.tableau()));
+                }
+            }
+            Command::Board => println!("{}", screen.board(game.tableau())),
+            Command::Undo => play(&mut game, Action::Undo, args.autoplay, screen),
+            Command::Hint => match game.hint() {
+                Some(action) => {
+                    let action = language.action_string(&action);
+                    println!("{}", language.format("Try {}.", &[&action]));
+                }
+                None => println!(
+                    "{}",
+                    language.tr(
+                        "There's nothing useful left to play. Try undoing, or start a new game."
+                    )
+                ),
+            },
+            Command::Moves => match game.legal_moves().as_slice() {
+                [] => println!("{}", language.tr("There are no moves left.")),
+                moves => println!("{}", join(moves, language)),
+            },
+            Command::History => match game.moves().as_slice() {
+                [] => println!("{}", language.tr("No moves have been played yet.")),
+                moves => println!("{}", join(moves, language)),
+            },
+            Command::Save if argument.is_empty() => {
+                println!("{}", language.tr("Type a file name after \"save\"."))
+            }
+            Command::Save => match fs::write(&argument, game.save()) {
+                Ok(()) => println!("{}", language.format("Saved to {}.", &[&argument])),
+                Err(e) => println!(
+                    "{}",
+                    language.format("Couldn't save to {}: {}", &[&argument, &e])
+                ),
+            },
+            Command::Highlight => {
+                screen.options.highlight = screen.options.highlight.next();
+                println!("{}", screen.board(game.tableau()));
+            }
+            Command::Palette => {
+                screen.options.palette = screen.options.palette.toggle();
+                println!("{}", screen.board(game.tableau()));
+            }
+            Command::Glyphs if screen.options.cards.toggle().fits_locale() => {
+                screen.options.cards = screen.options.cards.toggle();
+                println!("{}", screen.board(game.tableau()));
+            }
+            Command::Glyphs => println!(
+                "{}",
+                language.tr("The locale isn't UTF-8, so cards stay as text.")
+            ),
+            Command::Score => {
+                let score = scoring.score(&game, game.elapsed());
+                println!("{}", language.format("Score: {}", &[&score]));
+            }
+            Command::Splits => println!("{}", game.describe_times(language).join("\n")),
+            Command::Stats => match Stats::load(game.undo_mode()) {
+                Ok(stats) => {
+                    println!("{}", game.undo_mode().describe(language));
+                    println!("{}", stats.describe(language).join("\n"));
+                }
+                Err(e) => println!(
+                    "{}",
+                    language.format("Couldn't read the statistics: {}", &[&e])
+                ),
+            },
+            Command::Help => print_help(language),
+            Command::Quit => break,
+        }
+
+        keep(&mut autosave, &game, language);
+    }
+}
+
+/// Apply a move and show the result.
+fn play(game: &mut Game, action: Action, autoplay_on: bool, screen: Screen) {
+    let is_undo = action == Action::Undo;
+    let before = game.tableau().clone();
+    let played = game.history().len();
+    let language = screen.options.language;
+
+    match game.action(action) {
+        Ok(()) if is_undo => {
+            if !screen.describe {
+                println!("{}", screen.board(game.tableau()));
+            } else if let Some(description) =
+                DescriptionRenderer.describe_undo(&before, game.tableau(), language)
+            {
+                println!("{}", description);
+            }
+            if let Some(left) = game.undos_left() {
+                println!("{}", language.format("Undos left: {}", &[&left]));
+            }
+        }
+        Ok(()) => {
+            if autoplay_on && !is_undo {
+                autoplay(game);
+            }
+            screen.show_moves(game, played - 1);
+
+            if game.is_won() {
+                println!(
+                    "{}",
+                    language.tr("You win! Type \"new\" to play again, or \"quit\" to stop.")
+                );
+            }
+        }
+        Err(msg) => println!("{}", language.tr(msg)),
+    }
+}
+
+/// Ask before throwing away a game that's in progress, and count it as abandoned if it is.
+/// Anything but "y", or the language's word for it, keeps it.
+fn confirm_abandon(game: &Game, editor: &mut Editor, language: Language) -> bool {
+    if !is_in_progress(game) {
+        return true;
+    }
+
+    let abandon = ask(
+        "This game isn't finished. Abandon it? ({}/{})",
+        editor,
+        language,
+    );
+    if abandon {
+        record(game.undo_mode(), Outcome::Abandoned, language);
+    }
+    abandon
+}
+
+/// Offer to carry on with the game that was left unfinished last time, which is thrown away if
+/// the answer is anything but yes.
+fn resume(
+    autosave: &mut Option<Autosave>,
+    editor: &mut Editor,
+    language: Language,
+) -> Option<Game> {
+    let saved = autosave.as_mut()?;
+    let game = match saved.load() {
+        Ok(game) => game?,
+        Err(e) => {
+            let path = saved.path().display();
+            let message =
+                language.format("Couldn't read the unfinished game in {}: {}", &[&path, &e]);
+            println!("{}", message);
+            return None;
+        }
+    };
+
+    if ask(
+        "Carry on with the game left unfinished last time? ({}/{})",
+        editor,
+        language,
+    ) {
+        return Some(game);
+    }
+
+    record(game.undo_mode(), Outcome::Abandoned, language);
+    if let Err(e) = saved.remove() {
+        println!(
+            "{}",
+            language.format("Couldn't save the game for next time: {}", &[&e])
+        );
+        *autosave = None;
+    }
+    None
+}
+
+/// Ask a yes or no question, given as a template for the language's words for yes and no.
+fn ask(question: &'static str, editor: &mut Editor, language: Language) -> bool {
+    let (yes, no) = (language.tr("y"), language.tr("n"));
+    println!("{}", language.format(question, &[&yes, &no]));
+    read_line(editor, |_| vec![yes.to_string(), no.to_string()])
+        .is_some_and(|line| line.trim().eq_ignore_ascii_case(yes))
+}
+
+/// Add a finished game to the statistics for its undo mode.
+fn record(mode: UndoMode, outcome: Outcome, language: Language) {
+    if let Err(e) = Stats::record(mode, outcome) {
+        println!(
+            "{}",
+            language.format("Couldn't update the statistics: {}", &[&e])
+        );
+    }
+}
+
+/// Bring the autosave up to date after a command. If it can't be written, say so once and carry on
+/// without it.
+fn keep(autosave: &mut Option<Autosave>, game: &Game, language: Language) {
+    match autosave.as_mut().map(|saved| saved.update(game)) {
+        // The game left over from last time wasn't resumed, so it's been given up.
+        Some(Ok(Some(replaced))) => record(replaced.undo_mode(), Outcome::Abandoned, language),
+        Some(Err(e)) => {
+            println!(
+                "{}",
+                language.format("Couldn't save the game for next time: {}", &[&e])
+            );
+            *autosave = None;
+        }
+        _ => {}
+    }
+}
+
+/// The next line of input, or `None` at the end of it.
+fn read_line(editor: &mut Editor, complete: impl Fn(&str) -> Vec<String>) -> Option<String> {
+    editor.read_line("> ", complete).expect("I/O error")
+}
+
+fn join(moves: &[Action], language: Language) -> String {
+    moves
+        .iter()
+        .map(|action| language.action_string(action))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn print_help(language: Language) {
+    println!("{}", language.tr(MOVES_HELP));
+
+    for (names, _, argument, description) in COMMANDS {
+        let usage = format!("{} {}", names.join(", "), language.tr(argument));
+        println!("{:<24} {}", usage.trim_end(), language.tr(description));
+    }
+}
+
+/// Play a script without any interaction, returning the exit code. Problems with the input
+/// itself exit with 2, so as not to be mistaken for a game that wasn't won.
+fn batch(mut args: Args, path: &Path) -> i32 {
+    let language = args.language();
+    let input = if path == Path::new("-") {
+        io::read_to_string(io::stdin())
+            .map_err(|e| language.format("Couldn't read the script: {}", &[&e]))
+    } else {
+        read(path, language)
+    };
+    let result = input.and_then(|input| {
+        let script = Script::parse(&input, language)?;
+
+        if script.deal.is_some() || script.variant.is_some() {
+            if args.load.is_some() {
+                return Err(language
+                    .tr("The script chooses a deal, but a game was already loaded")
+                    .to_string());
+            }
+            args.deal = script.deal.or(args.deal);
+            args.variant = script.variant.or(args.variant);
+        }
+
+        let mut game = args.game()?;
+        Ok(script.run(&mut game, args.autoplay, args.screen()))
+    });
+
+    result.unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        2
+    })
+}
+
+/// Play every card that's safe to move to the foundations.
+fn autoplay(game: &mut Game) {
+    while let Some(action) = game.tableau().safe_foundation_move() {
+        game.action(action).unwrap();
+    }
+}
+
+/// Play the moves in a file, showing the board after each one. Moves are written the same way
+/// they're typed, separated by spaces or new lines, and anything after a `#` is a comment.
+fn replay(game: &mut Game, path: &Path, screen: Screen) -> Result<(), String> {
+    let language = screen.options.language;
+    let moves = read(path, language)?;
+    let moves = batch::lines(&moves).flat_map(|(_, line)| line.split_whitespace());
+
+    for (i, text) in moves.enumerate() {
+        language
+            .parse_action(text)
+            .and_then(|action| game.action(action))
+            .map_err(|message| {
+                language.format(
+                    "Move {} in {} ({}): {}",
+                    &[&(i + 1), &path.display(), &text, &language.tr(message)],
+                )
+            })?;
+
+        screen.show_moves(game, game.history().len() - 2);
+        println!("{}", language.format("Move {}: {}", &[&(i + 1), &text]));
+    }
+
+    Ok(())
+}
+
+fn read(path: &Path, language: Language) -> Result<String, String> {
+    fs::read_to_string(path)
+        .map_err(|e| language.format("Couldn't read {}: {}", &[&path.display(), &e]))
+}
+
+/// The command line options.
+#[derive(Debug, Default)]
+struct Args {
+    deal: Option<Deal>,
+    variant: Option<Variant>,
+    undo: Option<UndoMode>,
+    colours: Option<ColourSupport>,
+    language: Option<Language>,
+    describe: bool,
+    autoplay: bool,
+    load: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    batch: Option<PathBuf>,
+}
+
+/// How the board is shown: drawn with the display options, or described in words.
+#[derive(Clone, Copy, Debug)]
+struct Screen {
+    options: DisplayOptions,
+    describe: bool,
+}
+
+impl Screen {
+    fn board(&self, tableau: &Tableau) -> String {
+        if self.describe {
+            let view = View {
+                options: self.options,
+                ..View::default()
+            };
+            DescriptionRenderer.render(tableau, &view)
+        } else {
+            tableau.display(self.options).to_string()
+        }
+    }
+
+    /// Show the result of the moves played since position `from` in the game's history. In
+    /// words that's just what each move did, so that the whole board isn't read out every time.
+    fn show_moves(&self, game: &Game, from: usize) {
+        if !self.describe {
+            println!("{}", self.board(game.tableau()));
+            return;
+        }
+
+        for pair in game.history()[from..].windows(2) {
+            if let Some(description) =
+                DescriptionRenderer.describe_move(&pair[0], &pair[1], self.options.language)
+            {
+                println!("{}", description);
+            }
+        }
+    }
+}
+
+/// Which cards to deal when starting a new game.
+#[derive(Clone, Copy, Debug)]
+enum Deal {
+    Numbered(u32),
+    Seeded(u64),
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+
+        while let Some(arg) = args.next() {
+            let language = parsed.language();
+            let option = arg.as_str();
+            let mut value = || {
+                args.next()
+                    .ok_or(language.format("{} needs a value", &[&option]))
+            };
+            let needs_number = || language.format("{} needs a number", &[&option]);
+            let tr = |message| language.tr(message).to_string();
+
+            match option {
+                "--deal" | "--seed" if parsed.deal.is_some() => {
+                    return Err(tr("Only one of --deal and --seed can be given"))
+                }
+                "--deal" => {
+                    let number = value()?.parse().map_err(|_| needs_number())?;
+                    parsed.deal = Some(Deal::Numbered(number));
+                }
+                "--seed" => {
+                    let seed = value()?.parse().map_err(|_| needs_number())?;
+                    parsed.deal = Some(Deal::Seeded(seed));
+                }
+                "--variant" => parsed.variant = Some(value()?.parse().map_err(tr)?),
+                "--undo" => parsed.undo = Some(value()?.parse().map_err(tr)?),
+                "--colour" | "--color" => {
+                    parsed.colours = match value()?.as_str() {
+                        "auto" => None,
+                        mode => Some(mode.parse().map_err(tr)?),
+                    }
+                }
+                "--language" => parsed.language = Some(value()?.parse().map_err(tr)?),
+                "--describe" => parsed.describe = true,
+                "--autoplay" => parsed.autoplay = true,
+                "--no-autoplay" => parsed.autoplay = false,
+                "--load" => parsed.load = Some(value()?.into()),
+                "--replay" => parsed.replay = Some(value()?.into()),
+                "--batch" => parsed.batch = Some(value()?.into()),
+                "-h" | "--help" => {
+                    print!("{}", language.tr(USAGE));
+                    process::exit(0);
+                }
+                "-V" | "--version" => {
+                    println!("refcell {}", env!("CARGO_PKG_VERSION"));
+                    process::exit(0);
+                }
+                _ => return Err(language.format("Unknown option: {}", &[&arg])),
+            }
+        }
+
+        let language = parsed.language();
+        if parsed.load.is_some() && (parsed.deal.is_some() || parsed.variant.is_some()) {
+            return Err(language
+                .tr("A loaded game already has its deal and variant")
+                .to_string());
+        }
+        if parsed.load.is_some() && parsed.undo.is_some() {
+            return Err(language
+                .tr("A loaded game already has its undo mode")
+                .to_string());
+        }
+        if parsed.batch.is_some() && parsed.replay.is_some() {
+            return Err(language
+                .tr("A batch script can't be combined with --replay")
+                .to_string());
+        }
+
+        Ok(parsed)
+    }
+
+    /// Whether the options pick a game to play, rather than leaving it to chance.
+    fn chooses_game(&self) -> bool {
+        self.deal.is_some()
+            || self.variant.is_some()
+            || self.undo.is_some()
+            || self.load.is_some()
+            || self.replay.is_some()
+    }
+
+    /// The language chosen with `--language`, or else the one set in the environment.
+    fn language(&self) -> Language {
+        self.language.unwrap_or_else(Language::detect)
+    }
+
+    fn screen(&self) -> Screen {
+        let mut options = DisplayOptions::detect();
+        options.language = self.language();
+        if let Some(colours) = self.colours {
+            options.colours = colours;
+        }
+        Screen {
+            options,
+            describe: self.describe,
+        }
+    }
+
+    /// The game to start with, before any moves are replayed.
+    fn game(&self) -> Result<Game, String> {
+        let language = self.language();
+        if let Some(path) = &self.load {
+            return Game::load(&read(path, language)?).map_err(|message| {
+                language.format(
+                    "Couldn't load {}: {}",
+                    &[&path.display(), &language.tr(message)],
+                )
+            });
+        }
+
+        let variant = self.variant.unwrap_or_default();
+        let game = match self.deal {
+            Some(Deal::Numbered(number)) => Game::deal_numbered(number, variant),
+            Some(Deal::Seeded(seed)) => Game::deal_variant(Deck::seeded(seed), variant),
+            None => Game::deal_variant(Deck::shuffled(), variant),
+        };
+
+        Ok(game.with_undo_mode(self.undo.unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texts_are_translated() {
+        // The long texts are their own catalog keys, so any edit to one loses its translations
+        // until the catalog is updated to match.
+        let descriptions = COMMANDS.iter().map(|(_, _, _, description)| *description);
+        for text in [USAGE, INSTRUCTIONS, MOVES_HELP]
+            .into_iter()
+            .chain(descriptions)
+        {
+            for language in Language::ALL {
+                if language != Language::English {
+                    assert_ne!(text, language.tr(text), "not translated into {}", language);
+                }
+            }
+        }
+    }
+}
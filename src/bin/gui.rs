@@ -0,0 +1,466 @@
+use druid::kurbo::RoundedRect;
+use druid::piet::{FontBuilder, Text, TextLayout, TextLayoutBuilder};
+use druid::widget::prelude::*;
+use druid::widget::{Button, Flex, Label};
+use druid::{
+    AppLauncher, Color, Data, HotKey, MouseButton, Point, Rect, SysMods, Vec2, WidgetExt,
+    WindowDesc,
+};
+use rand::Rng;
+use refcell::*;
+use std::num::NonZeroU8;
+use std::rc::Rc;
+
+const CARD_WIDTH: f64 = 72.0;
+const CARD_HEIGHT: f64 = 100.0;
+const CORNER_RADIUS: f64 = 6.0;
+const GAP: f64 = 12.0;
+const FAN_OFFSET: f64 = 28.0;
+
+/// How far the mouse has to travel with the button down before a click becomes a drag.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+const FELT: Color = Color::rgb8(0x1b, 0x5e, 0x20);
+const EMPTY_SLOT: Color = Color::rgb8(0xa5, 0xd6, 0xa7);
+const CARD_FACE: Color = Color::WHITE;
+const CARD_EDGE: Color = Color::rgb8(0x42, 0x42, 0x42);
+const MARKED_EDGE: Color = Color::rgb8(0xff, 0xca, 0x28);
+
+fn main() {
+    let window = WindowDesc::new(build_ui)
+        .title("RefCell")
+        .window_size((GAP + 8.0 * (CARD_WIDTH + GAP), 760.0));
+
+    AppLauncher::with_window(window)
+        .launch(AppState::new(random_deal(), Language::detect()))
+        .expect("Unable to open a window");
+}
+
+fn build_ui() -> impl Widget<AppState> {
+    let button =
+        |label| Button::new(move |data: &AppState, _env: &Env| data.language.tr(label).to_string());
+    let toolbar = Flex::row()
+        .with_child(button("New game").on_click(|_, data: &mut AppState, _| data.new_game()))
+        .with_spacer(8.0)
+        .with_child(button("Restart").on_click(|_, data: &mut AppState, _| data.restart()))
+        .with_spacer(8.0)
+        .with_child(button("Undo").on_click(|_, data: &mut AppState, _| data.undo()))
+        .with_spacer(8.0)
+        .with_child(button("Redo").on_click(|_, data: &mut AppState, _| data.redo()))
+        .with_spacer(16.0)
+        .with_child(Label::new(|data: &AppState, _env: &Env| {
+            data.message
+                .clone()
+                .unwrap_or_else(|| data.language.format("Deal #{}", &[&data.deal]))
+        }))
+        .padding(GAP);
+
+    Flex::column()
+        .with_child(toolbar)
+        .with_flex_child(Board::default(), 1.0)
+        .background(FELT)
+}
+
+fn random_deal() -> u32 {
+    rand::thread_rng().gen_range(1, 1_000_001)
+}
+
+#[derive(Clone)]
+struct AppState {
+    deal: u32,
+    game: Rc<Game>,
+
+    /// Positions that have been undone, most recent last, so that they can be redone.
+    undone: Rc<Vec<Tableau>>,
+
+    message: Option<String>,
+    language: Language,
+}
+
+impl Data for AppState {
+    fn same(&self, other: &Self) -> bool {
+        self.deal == other.deal
+            && Rc::ptr_eq(&self.game, &other.game)
+            && Rc::ptr_eq(&self.undone, &other.undone)
+            && self.message == other.message
+            && self.language == other.language
+    }
+}
+
+impl AppState {
+    fn new(deal: u32, language: Language) -> Self {
+        Self {
+            deal,
+            game: Rc::new(Game::deal(Deck::numbered(deal))),
+            undone: Rc::new(Vec::new()),
+            message: None,
+            language,
+        }
+    }
+
+    fn new_game(&mut self) {
+        *self = Self::new(random_deal(), self.language);
+    }
+
+    fn restart(&mut self) {
+        *self = Self::new(self.deal, self.language);
+    }
+
+    /// Apply an action through the rule engine. Every change to the game goes through here, so
+    /// the data is always replaced rather than mutated and druid notices the difference.
+    fn apply(&mut self, action: Action) -> bool {
+        let mut game = (*self.game).clone();
+
+        match game.action(action) {
+            Ok(()) => {
+                self.message = if game.is_won() {
+                    Some(self.language.tr("You win!").to_string())
+                } else {
+                    None
+                };
+                self.game = Rc::new(game);
+                true
+            }
+            Err(message) => {
+                self.message = Some(self.language.tr(message).to_string());
+                false
+            }
+        }
+    }
+
+    fn play(&mut self, action: Action) {
+        if self.apply(action) {
+            self.undone = Rc::new(Vec::new());
+        }
+    }
+
+    fn undo(&mut self) {
+        let position = self.game.tableau().clone();
+
+        if self.apply(Action::Undo) {
+            let mut undone = (*self.undone).clone();
+            undone.push(position);
+            self.undone = Rc::new(undone);
+        }
+    }
+
+    fn redo(&mut self) {
+        let action = match self.undone.last() {
+            Some(position) => self.game.tableau().move_to(position),
+            None => {
+                self.message = Some(self.language.tr("There is nothing to redo.").to_string());
+                return;
+            }
+        };
+
+        if action.is_some_and(|action| self.apply(action)) {
+            let mut undone = (*self.undone).clone();
+            undone.pop();
+            self.undone = Rc::new(undone);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Pile {
+    Cell(u8),
+    Foundation(u8),
+    Cascade(u8),
+}
+
+impl Pile {
+    fn all() -> impl Iterator<Item = Pile> {
+        (0..4)
+            .map(Pile::Cell)
+            .chain((0..4).map(Pile::Foundation))
+            .chain((0..8).map(Pile::Cascade))
+    }
+
+    fn cards(self, tableau: &Tableau) -> &[Card] {
+        match self {
+            Pile::Cell(n) => tableau.cells[n as usize]
+                .peek()
+                .map_or(&[], std::slice::from_ref),
+            Pile::Foundation(n) => tableau.foundations[n as usize].cards(),
+            Pile::Cascade(n) => tableau.cascades[n as usize].cards(),
+        }
+    }
+
+    fn as_from_coordinate(self) -> Option<FromCoordinate> {
+        match self {
+            Pile::Cell(n) => Some(FromCoordinate::Cell(n)),
+            Pile::Foundation(_) => None,
+            Pile::Cascade(n) => Some(FromCoordinate::Cascade(n)),
+        }
+    }
+
+    fn as_to_coordinate(self) -> ToCoordinate {
+        match self {
+            Pile::Cell(n) => ToCoordinate::Cell(n),
+            Pile::Foundation(n) => ToCoordinate::Foundation(n),
+            Pile::Cascade(n) => ToCoordinate::Cascade(n),
+        }
+    }
+
+    /// Where the card at `depth` in the pile is drawn. Only cascades are fanned out.
+    fn card_origin(self, depth: usize) -> Point {
+        let column = |n: u8| GAP + f64::from(n) * (CARD_WIDTH + GAP);
+
+        match self {
+            Pile::Cell(n) => Point::new(column(n), GAP),
+            Pile::Foundation(n) => Point::new(column(n + 4), GAP),
+            Pile::Cascade(n) => Point::new(
+                column(n),
+                GAP * 3.0 + CARD_HEIGHT + depth as f64 * FAN_OFFSET,
+            ),
+        }
+    }
+
+    /// The pile under a point, and the depth of the card under it, if there is one. Dropping
+    /// anywhere below a cascade counts as dropping on it.
+    fn at(tableau: &Tableau, pos: Point) -> Option<(Pile, Option<usize>)> {
+        Pile::all().find_map(|pile| {
+            let cards = pile.cards(tableau);
+            let origin = pile.card_origin(0);
+            let bottom = match pile {
+                Pile::Cascade(_) => f64::INFINITY,
+                _ => origin.y + CARD_HEIGHT,
+            };
+
+            if !(origin.x..origin.x + CARD_WIDTH).contains(&pos.x)
+                || !(origin.y..bottom).contains(&pos.y)
+            {
+                return None;
+            }
+
+            let depth = (0..cards.len()).rev().find(|&depth| {
+                let y = pile.card_origin(depth).y;
+                (y..y + CARD_HEIGHT).contains(&pos.y)
+            });
+
+            Some((pile, depth))
+        })
+    }
+}
+
+/// A press on a card that might turn into a drag.
+struct Press {
+    pile: Pile,
+    depth: usize,
+    start: Point,
+    offset: Option<Vec2>,
+}
+
+/// The playing surface. Cards can be dragged from one pile to another, or moved by clicking the
+/// source pile and then the destination.
+#[derive(Default)]
+struct Board {
+    press: Option<Press>,
+    selected: Option<Pile>,
+}
+
+impl Board {
+    /// Finish a drag, or treat the press as a click: the first click picks the source pile and
+    /// the second picks the destination.
+    fn release(&mut self, data: &mut AppState, pos: Point) {
+        let tableau = data.game.tableau();
+        let target = Pile::at(tableau, pos).map(|(pile, _)| pile);
+
+        let (from, count) = match (self.press.take(), self.selected.take()) {
+            (Some(press), _) if press.offset.is_some() => {
+                (press.pile, press.pile.cards(tableau).len() - press.depth)
+            }
+            (_, Some(selected)) if Some(selected) != target => (selected, 0),
+            (Some(press), None) => {
+                self.selected = Some(press.pile);
+                return;
+            }
+            _ => return,
+        };
+
+        if let (Some(from_coordinate), Some(to)) = (from.as_from_coordinate(), target) {
+            if from != to {
+                data.play(Action::MoveCard {
+                    from: from_coordinate,
+                    to: to.as_to_coordinate(),
+                    count: u8::try_from(count)
+                        .ok()
+                        .filter(|&count| count > 1)
+                        .and_then(NonZeroU8::new),
+                });
+            }
+        }
+    }
+
+    fn paint_card(ctx: &mut PaintCtx, card: Option<&Card>, origin: Point, marked: bool) {
+        let shape = RoundedRect::from_rect(
+            Rect::from_origin_size(origin, (CARD_WIDTH, CARD_HEIGHT)),
+            CORNER_RADIUS,
+        );
+
+        let card = match card {
+            Some(card) => card,
+            None => {
+                ctx.stroke(shape, &EMPTY_SLOT, 1.0);
+                return;
+            }
+        };
+
+        ctx.fill(shape, &CARD_FACE);
+        if marked {
+            ctx.stroke(shape, &MARKED_EDGE, 3.0);
+        } else {
+            ctx.stroke(shape, &CARD_EDGE, 1.0);
+        }
+
+        let colour = match card.get_suit().colour(Palette::default()) {
+            SuitColour::Red => Color::rgb8(0xc6, 0x28, 0x28),
+            SuitColour::Blue => Color::rgb8(0x15, 0x65, 0xc0),
+            SuitColour::Green => Color::rgb8(0x2e, 0x7d, 0x32),
+            SuitColour::Black => Color::BLACK,
+        };
+
+        let corner = format!("{}{}", card.get_rank(), card.get_suit());
+        let small = ctx.text().new_font_by_name("sans-serif", 16.0).build();
+        let large = ctx.text().new_font_by_name("sans-serif", 36.0).build();
+
+        if let (Ok(small), Ok(large)) = (small, large) {
+            let corner = ctx.text().new_text_layout(&small, &corner, None).build();
+            let suit = card.get_suit().to_string();
+            let centre = ctx.text().new_text_layout(&large, &suit, None).build();
+
+            if let (Ok(corner), Ok(centre)) = (corner, centre) {
+                let centre_offset = Vec2::new(
+                    (CARD_WIDTH - centre.width()) / 2.0,
+                    CARD_HEIGHT / 2.0 + 12.0,
+                );
+
+                ctx.draw_text(&corner, origin + Vec2::new(6.0, 18.0), &colour);
+                ctx.draw_text(&centre, origin + centre_offset, &colour);
+            }
+        }
+    }
+}
+
+impl Widget<AppState> for Board {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut AppState, _env: &Env) {
+        match event {
+            Event::WindowConnected => ctx.request_focus(),
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                self.press = match Pile::at(data.game.tableau(), mouse.pos) {
+                    Some((pile, Some(depth))) if pile.as_from_coordinate().is_some() => {
+                        Some(Press {
+                            pile,
+                            depth,
+                            start: mouse.pos,
+                            offset: None,
+                        })
+                    }
+                    _ => None,
+                };
+                ctx.set_active(true);
+                ctx.request_focus();
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some(press) = &mut self.press {
+                    let offset = mouse.pos - press.start;
+                    if press.offset.is_some() || offset.hypot() > DRAG_THRESHOLD {
+                        press.offset = Some(offset);
+                        self.selected = None;
+                    }
+                }
+                ctx.request_paint();
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left => {
+                ctx.set_active(false);
+                self.release(data, mouse.pos);
+                ctx.request_paint();
+            }
+            Event::KeyDown(key) => {
+                if HotKey::new(SysMods::Cmd, "z").matches(key) {
+                    data.undo();
+                } else if HotKey::new(SysMods::CmdShift, "Z").matches(key)
+                    || HotKey::new(SysMods::Cmd, "y").matches(key)
+                {
+                    data.redo();
+                } else if HotKey::new(SysMods::Cmd, "n").matches(key) {
+                    data.new_game();
+                } else {
+                    return;
+                }
+
+                self.press = None;
+                self.selected = None;
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &AppState, _: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            ctx.register_for_focus();
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &AppState, data: &AppState, _env: &Env) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &AppState, _: &Env) -> Size {
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &AppState, _env: &Env) {
+        let tableau = data.game.tableau();
+        let dragged = self
+            .press
+            .as_ref()
+            .and_then(|press| press.offset.map(|offset| (press.pile, press.depth, offset)));
+
+        let view = View {
+            selected: self.selected.and_then(Pile::as_from_coordinate),
+            ..View::default()
+        };
+
+        for pile in Pile::all() {
+            let cards = pile.cards(tableau);
+            let marked = |depth: usize| {
+                pile.as_from_coordinate()
+                    .is_some_and(|from| view.is_marked(tableau, from, depth))
+            };
+
+            match pile {
+                Pile::Cascade(_) if !cards.is_empty() => {
+                    for (depth, card) in cards.iter().enumerate() {
+                        if !matches!(dragged, Some((p, d, _)) if p == pile && depth >= d) {
+                            Self::paint_card(
+                                ctx,
+                                Some(card),
+                                pile.card_origin(depth),
+                                marked(depth),
+                            );
+                        }
+                    }
+                }
+                _ => {
+                    let card = cards
+                        .last()
+                        .filter(|_| !matches!(dragged, Some((p, ..)) if p == pile));
+                    Self::paint_card(ctx, card, pile.card_origin(0), card.is_some() && marked(0));
+                }
+            }
+        }
+
+        // Dragged cards are drawn last so that they float over everything else.
+        if let Some((pile, depth, offset)) = dragged {
+            let cards = pile.cards(tableau);
+
+            for (depth, card) in cards.iter().enumerate().skip(depth) {
+                Self::paint_card(ctx, Some(card), pile.card_origin(depth) + offset, false);
+            }
+        }
+    }
+}
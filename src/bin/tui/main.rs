@@ -0,0 +1,1378 @@
+use rand::Rng;
+use record::Recorder;
+use refcell::*;
+use std::io;
+use std::io::prelude::*;
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+use std::{env, iter, process};
+use termion::event::{Event, Key, MouseButton, MouseEvent};
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::{color, cursor, input, screen, style};
+use theme::Theme;
+
+mod record;
+mod theme;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+const FRAME_DURATION: Duration = Duration::from_millis(16);
+const ANIMATION_FRAMES: u16 = 10;
+
+const USAGE: &str = "\
+Usage: tui [--record <file>] [--language <code>] [--undo <mode>]
+
+Options:
+  --record <file>    Save the session as an asciicast v2 recording
+  --language <code>  Language for messages: en or fr (the default comes from LANG)
+  --undo <mode>      costly (the default) to take points off for each undo, free,
+                     off, or the number of undos allowed
+  -h, --help         Show this message
+";
+
+fn main() -> io::Result<()> {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|message| {
+        eprint!("{}\n\n{}", message, Language::detect().tr(USAGE));
+        process::exit(2);
+    });
+
+    // A broken config file shouldn't stop the game from starting, so report it in the status bar.
+    let (themes, theme_error) = match Theme::load() {
+        Ok(Some(theme)) if theme.name == "custom" => {
+            (iter::once(theme).chain(Theme::builtin()).collect(), None)
+        }
+        Ok(Some(theme)) => {
+            let mut themes = Theme::builtin();
+            let i = themes.iter().position(|t| t.name == theme.name).unwrap();
+            themes.rotate_left(i);
+            (themes, None)
+        }
+        Ok(None) => (Theme::builtin(), None),
+        Err(e) => (Theme::builtin(), Some(e.to_string())),
+    };
+
+    let _hide_cursor = termion::cursor::HideCursor::from(io::stdout());
+    let terminal =
+        input::MouseTerminal::from(screen::AlternateScreen::from(io::stdout().into_raw_mode()?));
+    let terminal = match &args.record {
+        Some(path) => Recorder::to_file(terminal, path)?,
+        None => Recorder::new(terminal),
+    };
+    let mut events = termion::async_stdin().events();
+
+    // The TUI always draws to a terminal, so it can use text attributes even without colour.
+    let colours = ColourSupport::detect().max(ColourSupport::Monochrome);
+    let themes = themes
+        .into_iter()
+        .map(|theme| theme.for_colours(colours))
+        .collect();
+
+    let game = Game::deal_numbered(random_deal(), Variant::default())
+        .with_undo_mode(args.undo.unwrap_or_default());
+    let mut ui = Ui::new(terminal, game, themes, colours);
+    ui.options.language = args.language.unwrap_or_else(Language::detect);
+    ui.message = theme_error;
+    match Scoring::load() {
+        Ok(scoring) => ui.scoring = scoring,
+        Err(e) => {
+            let path = Scoring::config_path().unwrap_or_default();
+            let language = ui.options.language;
+            ui.message = Some(language.format(
+                "Couldn't read the scoring config {}: {}",
+                &[&path.display(), &e],
+            ));
+        }
+    }
+    let mut input = String::new();
+    let mut mode = Mode::Play;
+    let mut elapsed_tenths = 0;
+
+    ui.redraw()?;
+
+    // Offer the game that was left unfinished last time, if there is one, unless an undo mode was
+    // asked for.
+    let language = ui.options.language;
+    let autosave = ui.autosave.as_mut().filter(|_| args.undo.is_none());
+    match autosave.map(Autosave::load) {
+        Some(Ok(Some(game))) => {
+            let question = language.format(
+                "Carry on with the game left unfinished last time? ({}/{})",
+                &[&language.tr("y"), &language.tr("n")],
+            );
+            ui.render_box(&[question])?;
+            mode = Mode::Resume(game);
+        }
+        Some(Err(e)) => {
+            let path = ui.autosave.as_ref().unwrap().path().display();
+            ui.message =
+                Some(language.format("Couldn't read the unfinished game in {}: {}", &[&path, &e]));
+            ui.render_status()?;
+        }
+        _ => {}
+    }
+
+    loop {
+        let event = match events.next() {
+            Some(event) => event?,
+            None => {
+                // Keep the clock in the status bar ticking over.
+                if ui.game.elapsed().as_millis() / 100 != elapsed_tenths {
+                    elapsed_tenths = ui.game.elapsed().as_millis() / 100;
+                    ui.render_status()?;
+                }
+
+                sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+
+        let key = match (&mode, &event) {
+            (Mode::Play, _) => None,
+            (_, Event::Key(key)) => Some(*key),
+            _ => continue,
+        };
+
+        if let Some(key) = key {
+            mode = match (mode, key) {
+                (Mode::Menu, key) => match MENU.iter().find(|(k, _, _)| *k == key) {
+                    Some((_, MenuChoice::Random, _)) => ui.confirm(NewGame::Random)?,
+                    Some((_, MenuChoice::Restart, _)) => ui.confirm(NewGame::Restart)?,
+                    Some((_, MenuChoice::Numbered, _)) => {
+                        ui.render_deal_prompt("")?;
+                        Mode::DealNumber(String::new())
+                    }
+                    None => {
+                        ui.redraw()?;
+                        Mode::Play
+                    }
+                },
+                (Mode::DealNumber(mut number), Key::Char(c)) if c.is_ascii_digit() => {
+                    // Only take digits that keep the number in range for a deal.
+                    let longer = format!("{}{}", number, c);
+                    if number.len() < 10 && longer.parse::<u32>().is_ok() {
+                        number = longer;
+                    }
+                    ui.render_deal_prompt(&number)?;
+                    Mode::DealNumber(number)
+                }
+                (Mode::DealNumber(mut number), Key::Backspace) => {
+                    number.pop();
+                    ui.render_deal_prompt(&number)?;
+                    Mode::DealNumber(number)
+                }
+                (Mode::DealNumber(number), Key::Char('\n')) => match number.parse() {
+                    Ok(number) => ui.confirm(NewGame::Numbered(number))?,
+                    Err(_) => {
+                        ui.render_deal_prompt(&number)?;
+                        Mode::DealNumber(number)
+                    }
+                },
+                (Mode::Confirm(new_game), Key::Char(c)) if ui.is_yes(c) => {
+                    input.clear();
+                    ui.selected = None;
+                    ui.record(ui.game.undo_mode(), Outcome::Abandoned);
+                    ui.start(new_game)?;
+                    Mode::Play
+                }
+                (Mode::Resume(game), Key::Char(c)) if ui.is_yes(c) => {
+                    ui.game = game;
+                    ui.redraw()?;
+                    Mode::Play
+                }
+                (Mode::Resume(game), _) => {
+                    ui.record(game.undo_mode(), Outcome::Abandoned);
+                    if let Some(Err(e)) = ui.autosave.as_mut().map(Autosave::remove) {
+                        ui.autosave_failed(e);
+                    }
+                    ui.redraw()?;
+                    Mode::Play
+                }
+                (Mode::DealNumber(number), key) if key != Key::Esc => Mode::DealNumber(number),
+                _ => {
+                    ui.redraw()?;
+                    Mode::Play
+                }
+            };
+            continue;
+        }
+
+        let command = match event {
+            Event::Key(key) => Command::for_key(key),
+            _ => None,
+        };
+
+        match (command, event) {
+            (Some(Command::Quit), _) => break,
+            (Some(Command::Undo), _) => {
+                input.clear();
+                ui.play(Action::Undo)?;
+            }
+            (Some(Command::Replay), _) => {
+                input.clear();
+                ui.replay()?;
+            }
+            (Some(Command::Highlight), _) => {
+                ui.options.highlight = ui.options.highlight.next();
+                ui.redraw()?;
+            }
+            (Some(Command::Help), _) => {
+                mode = Mode::Help;
+                ui.render_help()?;
+            }
+            (Some(Command::Splits), _) => {
+                mode = Mode::Splits;
+                let lines = ui.game.describe_times(ui.options.language);
+                ui.render_box(&lines)?;
+            }
+            (Some(Command::Stats), _) => {
+                mode = Mode::Stats;
+                ui.render_stats()?;
+            }
+            (Some(Command::Menu), _) => {
+                mode = Mode::Menu;
+                ui.render_menu()?;
+            }
+            (Some(Command::Palette), _) => {
+                ui.options.palette = ui.options.palette.toggle();
+                ui.redraw()?;
+            }
+            (Some(Command::Theme), _) => ui.next_theme()?,
+            (Some(Command::Cancel), _) => {
+                input.clear();
+                if ui.selected.take().is_some() {
+                    ui.redraw()?;
+                }
+            }
+            (None, Event::Key(Key::Char(c))) => {
+                input.push(c);
+
+                let coords = input.split_once('+').map_or(input.as_str(), |(_, c)| c);
+                if coords.len() >= 2 {
+                    match ui.options.language.parse_action(&input) {
+                        Ok(action) => ui.play(action)?,
+                        Err(message) => ui.show_message(message)?,
+                    }
+                    input.clear();
+                }
+            }
+            (None, Event::Mouse(MouseEvent::Press(MouseButton::Left, x, y))) => {
+                let pile = ui.scale()?.pile_at((x, y));
+
+                match (ui.selected.take(), pile) {
+                    (Some(from), Some(to)) if from != to => {
+                        match format!("{}{}", from.as_char(), to.as_char()).parse() {
+                            Ok(action) => ui.play(action)?,
+                            Err(message) => {
+                                ui.redraw()?;
+                                ui.show_message(message)?
+                            }
+                        }
+                    }
+                    (None, Some(pile)) => {
+                        ui.selected = Some(pile);
+                        ui.redraw()?;
+                    }
+                    (Some(_), _) => ui.redraw()?,
+                    (None, None) => {}
+                }
+            }
+            (None, _) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// The command line options.
+#[derive(Debug, Default)]
+struct Args {
+    record: Option<PathBuf>,
+    language: Option<Language>,
+    undo: Option<UndoMode>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+
+        while let Some(arg) = args.next() {
+            let language = parsed.language.unwrap_or_else(Language::detect);
+            let option = arg.as_str();
+            let mut value = || {
+                args.next()
+                    .ok_or(language.format("{} needs a value", &[&option]))
+            };
+
+            match option {
+                "--record" => parsed.record = Some(value()?.into()),
+                "--language" => {
+                    let code = value()?;
+                    parsed.language = Some(code.parse().map_err(|e| language.tr(e))?);
+                }
+                "--undo" => parsed.undo = Some(value()?.parse().map_err(|e| language.tr(e))?),
+                "-h" | "--help" => {
+                    print!("{}", language.tr(USAGE));
+                    process::exit(0);
+                }
+                _ => return Err(language.format("Unknown option: {}", &[&arg])),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Command {
+    Quit,
+    Undo,
+    Replay,
+    Highlight,
+    Help,
+    Splits,
+    Stats,
+    Menu,
+    Palette,
+    Theme,
+    Cancel,
+}
+
+/// What the keyboard is currently being used for.
+enum Mode {
+    Play,
+    Help,
+    Splits,
+    Stats,
+    Menu,
+    DealNumber(String),
+    Confirm(NewGame),
+    Resume(Game),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MenuChoice {
+    Random,
+    Restart,
+    Numbered,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NewGame {
+    Random,
+    Restart,
+    Numbered(u32),
+}
+
+/// The options on the new game menu, in the order they're listed.
+const MENU: &[(Key, MenuChoice, &str)] = &[
+    (
+        Key::Char('n'),
+        MenuChoice::Random,
+        "Start a new random deal",
+    ),
+    (Key::Char('r'), MenuChoice::Restart, "Restart this deal"),
+    (
+        Key::Char('d'),
+        MenuChoice::Numbered,
+        "Choose a deal by number",
+    ),
+];
+
+/// Every key binding, in the order they're listed on the help screen. Any other printable
+/// character is treated as part of a move.
+const KEY_BINDINGS: &[(&[Key], Command, &str)] = &[
+    (&[Key::Char('u')], Command::Undo, "Undo the last move"),
+    (&[Key::Char('r')], Command::Replay, "Replay the game so far"),
+    (
+        &[Key::Char('h')],
+        Command::Highlight,
+        "Change which cards are highlighted",
+    ),
+    (
+        &[Key::Char('f')],
+        Command::Palette,
+        "Switch between two and four suit colours",
+    ),
+    (&[Key::Char('t')], Command::Theme, "Switch colour theme"),
+    (
+        &[Key::Esc],
+        Command::Cancel,
+        "Cancel the move being entered",
+    ),
+    (
+        &[Key::Char('n')],
+        Command::Menu,
+        "New game, restart, or choose a deal",
+    ),
+    (
+        &[Key::Char('p')],
+        Command::Splits,
+        "Show the time taken and the splits",
+    ),
+    (&[Key::Char('s')], Command::Stats, "Show your statistics"),
+    (&[Key::Char('?')], Command::Help, "Show this screen"),
+    (&[Key::Char('q'), Key::Ctrl('c')], Command::Quit, "Quit"),
+];
+
+/// Input that doesn't map to a single key, listed on the help screen after the key bindings.
+const GESTURES: &[(&str, &str)] = &[
+    ("1-8", "Cascades, as a source or destination"),
+    ("a-d", "Free cells, as a source or destination"),
+    ("w-z", "Foundations, as a destination"),
+    ("+", "Before a move, move as many cards as possible"),
+    ("Click", "Select a pile, then click where it should go"),
+];
+
+const RULES: &str = "\
+Build the foundations up by suit from ace to king.
+Cascades build down in alternating colours. Any card
+can go in a free cell or an empty cascade. Stacks can
+be moved when there is enough free space to do so
+one card at a time.";
+
+impl Command {
+    fn for_key(key: Key) -> Option<Self> {
+        KEY_BINDINGS
+            .iter()
+            .find(|(keys, _, _)| keys.contains(&key))
+            .map(|(_, command, _)| *command)
+    }
+}
+
+fn key_name(key: Key, language: Language) -> String {
+    match key {
+        Key::Char(c) => c.to_string(),
+        Key::Ctrl(c) => format!("^{}", c.to_ascii_uppercase()),
+        Key::Esc => language.tr("Esc").to_string(),
+        key => format!("{:?}", key),
+    }
+}
+
+/// Pick a deal number at random, from the same range as Microsoft FreeCell.
+fn random_deal() -> u32 {
+    rand::thread_rng().gen_range(1, 1_000_001)
+}
+
+/// Everything the TUI needs to draw a frame, apart from the tableau itself, which may be a past
+/// position during a replay.
+struct Ui<W: Write> {
+    terminal: W,
+    game: Game,
+    autosave: Option<Autosave>,
+    options: DisplayOptions,
+    selected: Option<Pile>,
+    themes: Vec<Theme>,
+    theme_index: usize,
+    message: Option<String>,
+    scoring: Scoring,
+
+    /// Whether the game has been won and added to the statistics.
+    recorded: bool,
+}
+
+impl<W: Write> Ui<W> {
+    fn new(terminal: W, game: Game, themes: Vec<Theme>, colours: ColourSupport) -> Self {
+        Self {
+            terminal,
+            game,
+            autosave: Autosave::new("tui"),
+            options: DisplayOptions {
+                colours,
+                ..DisplayOptions::default()
+            },
+            selected: None,
+            themes,
+            theme_index: 0,
+            message: None,
+            scoring: Scoring::default(),
+            recorded: false,
+        }
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.themes[self.theme_index]
+    }
+
+    fn next_theme(&mut self) -> io::Result<()> {
+        self.theme_index = (self.theme_index + 1) % self.themes.len();
+        let name = &self.theme().name;
+        self.message = Some(self.options.language.format("Theme: {}", &[name]));
+        self.redraw()
+    }
+
+    /// Whether a key answers yes to a question, in the player's language.
+    fn is_yes(&self, c: char) -> bool {
+        let yes = self.options.language.tr("y");
+        yes.chars().eq(iter::once(c.to_ascii_lowercase()))
+    }
+
+    /// Ask before abandoning a game that's in progress, otherwise go straight to the new game.
+    fn confirm(&mut self, new_game: NewGame) -> io::Result<Mode> {
+        if self.game.history().len() > 1 && !self.game.is_won() {
+            let language = self.options.language;
+            let question = language.format(
+                "This game isn't finished. Abandon it? ({}/{})",
+                &[&language.tr("y"), &language.tr("n")],
+            );
+            self.render_box(&[question])?;
+            Ok(Mode::Confirm(new_game))
+        } else {
+            self.start(new_game)?;
+            Ok(Mode::Play)
+        }
+    }
+
+    fn start(&mut self, new_game: NewGame) -> io::Result<()> {
+        let variant = self.game.tableau().variant;
+        let undo_mode = self.game.undo_mode();
+        match new_game {
+            NewGame::Random => {
+                self.game = Game::deal_numbered(random_deal(), variant).with_undo_mode(undo_mode)
+            }
+            NewGame::Restart => self.game.restart(),
+            NewGame::Numbered(number) => {
+                self.game = Game::deal_numbered(number, variant).with_undo_mode(undo_mode)
+            }
+        }
+
+        self.message = None;
+        self.recorded = false;
+        self.keep();
+        self.redraw()
+    }
+
+    /// Add a finished game to the statistics for its undo mode, saying so in the status bar if
+    /// they can't be saved.
+    fn record(&mut self, mode: UndoMode, outcome: Outcome) {
+        if let Err(e) = Stats::record(mode, outcome) {
+            let language = self.options.language;
+            self.message = Some(language.format("Couldn't update the statistics: {}", &[&e]));
+        }
+    }
+
+    /// Bring the autosave up to date with the game.
+    fn keep(&mut self) {
+        match self.autosave.as_mut().map(|saved| saved.update(&self.game)) {
+            // The game left over from last time wasn't resumed, so it's been given up.
+            Some(Ok(Some(replaced))) => self.record(replaced.undo_mode(), Outcome::Abandoned),
+            Some(Err(e)) => self.autosave_failed(e),
+            _ => {}
+        }
+    }
+
+    /// Say that the game can't be kept for next time, and stop trying.
+    fn autosave_failed(&mut self, e: io::Error) {
+        let language = self.options.language;
+        self.message = Some(language.format("Couldn't save the game for next time: {}", &[&e]));
+        self.autosave = None;
+    }
+
+    /// The card size to use, leaving the bottom line free for the status bar.
+    fn scale(&self) -> io::Result<UiScale> {
+        let (width, height) = termion::terminal_size()?;
+        Ok(UiScale::from_terminal_size((
+            width,
+            height.saturating_sub(1),
+        )))
+    }
+
+    /// Apply an action to the game, animating the result. Once the game is won, any cards that
+    /// remain on the board are played to the foundations.
+    fn play(&mut self, action: Action) -> io::Result<()> {
+        let is_undo = action == Action::Undo;
+        let before = self.game.tableau().clone();
+
+        match self.game.action(action) {
+            Ok(()) => {
+                let language = self.options.language;
+                self.message = self
+                    .game
+                    .undos_left()
+                    .filter(|_| is_undo)
+                    .map(|left| language.format("Undos left: {}", &[&left]));
+                self.animate(&before, &self.game.tableau().clone())?;
+
+                if !is_undo && self.game.is_won() {
+                    if !self.recorded {
+                        let moves = self.game.moves().len();
+                        let time = self.game.elapsed();
+                        let score = self.scoring.score(&self.game, time);
+                        self.record(self.game.undo_mode(), Outcome::Won { moves, time, score });
+                        self.recorded = true;
+                    }
+
+                    while let Some(action) = self.game.tableau().foundation_move() {
+                        let before = self.game.tableau().clone();
+                        self.game.action(action).unwrap();
+                        self.animate(&before, &self.game.tableau().clone())?;
+                    }
+                }
+
+                self.keep();
+                self.render_status()
+            }
+            Err(message) => self.show_message(message),
+        }
+    }
+
+    /// Play back every move in the game so far, starting from the deal.
+    fn replay(&mut self) -> io::Result<()> {
+        let history = self.game.history().to_vec();
+
+        self.render(&history[0], &[], None)?;
+
+        history
+            .windows(2)
+            .try_for_each(|pair| self.animate(&pair[0], &pair[1]))
+    }
+
+    fn animate(&mut self, before: &Tableau, after: &Tableau) -> io::Result<()> {
+        if let Some(animation) = Animation::new(before, after, self.scale()?) {
+            for frame in 0..ANIMATION_FRAMES {
+                self.render(
+                    after,
+                    &animation.cards_at(frame),
+                    Some((animation.to, animation.cards.len())),
+                )?;
+                sleep(FRAME_DURATION);
+            }
+        }
+
+        self.render(after, &[], None)
+    }
+
+    fn view(&self) -> View {
+        View {
+            options: self.options,
+            selected: self.selected.and_then(Pile::as_from_coordinate),
+        }
+    }
+
+    /// Draw the current position from scratch.
+    fn redraw(&mut self) -> io::Result<()> {
+        let tableau = self.game.tableau().clone();
+        self.render(&tableau, &[], None)
+    }
+
+    /// Show one of the library's messages in the status bar, in the player's language.
+    fn show_message(&mut self, message: &'static str) -> io::Result<()> {
+        self.message = Some(self.options.language.tr(message).to_string());
+        self.render_status()
+    }
+
+    /// Draw a complete frame: the tableau, minus the top `hidden.1` cards of pile `hidden.0`, with
+    /// any cards that are in motion drawn on top at the given screen positions.
+    fn render(
+        &mut self,
+        tableau: &Tableau,
+        moving: &[(Card, (u16, u16))],
+        hidden: Option<(Pile, usize)>,
+    ) -> io::Result<()> {
+        let mut renderer = TuiRenderer {
+            scale: self.scale()?,
+            theme: self.theme(),
+            moving,
+            hidden,
+        };
+        let mut frame = renderer.render(tableau, &self.view())?;
+        self.write_status(&mut frame)?;
+
+        self.terminal.write_all(&frame)?;
+        self.terminal.flush()
+    }
+
+    /// Draw the list of key bindings and a summary of the rules in a box over the board.
+    fn render_help(&mut self) -> io::Result<()> {
+        let language = self.options.language;
+        let lines: Vec<String> = KEY_BINDINGS
+            .iter()
+            .map(|(keys, _, description)| {
+                let keys: Vec<String> = keys.iter().map(|key| key_name(*key, language)).collect();
+                (keys.join(", "), *description)
+            })
+            .chain(
+                GESTURES
+                    .iter()
+                    .map(|(gesture, description)| (language.tr(gesture).to_string(), *description)),
+            )
+            .map(|(input, description)| format!("{:<7} {}", input, language.tr(description)))
+            .chain(iter::once(String::new()))
+            .chain(language.tr(RULES).lines().map(str::to_string))
+            .collect();
+
+        self.render_box(&lines)
+    }
+
+    fn render_stats(&mut self) -> io::Result<()> {
+        let language = self.options.language;
+        let mode = self.game.undo_mode();
+        let lines = match Stats::load(mode) {
+            Ok(stats) => iter::once(mode.describe(language))
+                .chain(stats.describe(language))
+                .collect(),
+            Err(e) => vec![language.format("Couldn't read the statistics: {}", &[&e])],
+        };
+
+        self.render_box(&lines)
+    }
+
+    fn render_menu(&mut self) -> io::Result<()> {
+        let language = self.options.language;
+        let lines: Vec<String> = MENU
+            .iter()
+            .map(|(key, _, description)| {
+                format!(
+                    "{:<3} {}",
+                    key_name(*key, language),
+                    language.tr(description)
+                )
+            })
+            .chain(iter::once(format!(
+                "{:<3} {}",
+                key_name(Key::Esc, language),
+                language.tr("Back to the game")
+            )))
+            .collect();
+
+        self.render_box(&lines)
+    }
+
+    fn render_deal_prompt(&mut self, number: &str) -> io::Result<()> {
+        let language = self.options.language;
+        self.render_box(&[
+            language.format(
+                "Deal number: {}",
+                &[&format!("{:<10}", format!("{}_", number))],
+            ),
+            String::new(),
+            language.tr("Enter to start, Esc to cancel").to_string(),
+        ])
+    }
+
+    /// Draw a box in the middle of the screen, on top of whatever is already there.
+    fn render_box(&mut self, lines: &[String]) -> io::Result<()> {
+        let (width, height) = termion::terminal_size()?;
+        let box_width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+            + 4;
+        let box_height = lines.len() + 2;
+        let col = (width as usize).saturating_sub(box_width) / 2 + 1;
+        let row = (height as usize).saturating_sub(box_height) / 2 + 1;
+
+        let mut frame = Vec::new();
+
+        iter::once("")
+            .chain(lines.iter().map(String::as_str))
+            .chain(iter::once(""))
+            .enumerate()
+            .try_for_each(|(i, line)| {
+                write!(
+                    frame,
+                    "{goto}{fg}{bg}  {line:<width$}  {fg_reset}{bg_reset}",
+                    goto = cursor::Goto(col as u16, (row + i) as u16),
+                    fg = self.theme().panel_text.fg(),
+                    bg = self.theme().panel.bg(),
+                    line = line,
+                    width = box_width - 4,
+                    fg_reset = color::Fg(color::Reset),
+                    bg_reset = color::Bg(color::Reset),
+                )
+            })?;
+
+        self.terminal.write_all(&frame)?;
+        self.terminal.flush()
+    }
+
+    /// Redraw just the status bar, leaving the rest of the screen alone.
+    fn render_status(&mut self) -> io::Result<()> {
+        let mut frame = Vec::new();
+        self.write_status(&mut frame)?;
+
+        self.terminal.write_all(&frame)?;
+        self.terminal.flush()
+    }
+
+    fn write_status(&self, terminal: &mut impl Write) -> io::Result<()> {
+        let (width, height) = termion::terminal_size()?;
+        let tableau = self.game.tableau();
+        let time = self.game.elapsed();
+        let language = self.options.language;
+
+        let status = language.format(
+            " Deal #{}  Moves: {}  Time: {}  Score: {}  Free cells: {}  Empty cascades: {}  Max stack: {}",
+            &[
+                &self
+                    .game
+                    .number()
+                    .map_or_else(|| "?".to_string(), |number| number.to_string()),
+                &(self.game.history().len() - 1),
+                &format_time(time),
+                &self.scoring.score(&self.game, time),
+                &tableau.cells.iter().filter(|cell| cell.is_empty()).count(),
+                &tableau
+                    .cascades
+                    .iter()
+                    .filter(|cascade| cascade.is_empty())
+                    .count(),
+                &tableau.max_stack_size(false),
+            ],
+        );
+
+        // Right-align the message if there's room, but always keep it clear of the counters.
+        let message = self
+            .message
+            .as_deref()
+            .unwrap_or_else(|| language.tr("Press ? for help"));
+        let padding = (width as usize)
+            .saturating_sub(status.chars().count() + message.chars().count() + 1)
+            .max(2);
+        let line: String = format!("{}{}{} ", status, " ".repeat(padding), message)
+            .chars()
+            .chain(iter::repeat(' '))
+            .take(width as usize)
+            .collect();
+
+        write!(
+            terminal,
+            "{goto}{fg}{bg}{line}{fg_reset}{bg_reset}",
+            goto = cursor::Goto(1, height),
+            fg = self.theme().panel_text.fg(),
+            bg = self.theme().panel.bg(),
+            line = line,
+            fg_reset = color::Fg(color::Reset),
+            bg_reset = color::Bg(color::Reset),
+        )
+    }
+}
+
+/// A card or stack of cards sliding from one pile to another.
+struct Animation {
+    cards: Vec<Card>,
+    from: Vec<(u16, u16)>,
+    to: Pile,
+    destination: Vec<(u16, u16)>,
+}
+
+impl Animation {
+    /// Work out which cards moved between two consecutive positions. Returns `None` if the
+    /// tableaux don't differ by exactly one move.
+    fn new(before: &Tableau, after: &Tableau, scale: UiScale) -> Option<Self> {
+        let changes: Vec<(Pile, isize)> = Pile::all()
+            .map(|pile| {
+                (
+                    pile,
+                    pile.cards(after).len() as isize - pile.cards(before).len() as isize,
+                )
+            })
+            .filter(|(_, change)| *change != 0)
+            .collect();
+
+        let (from, to, count) = match changes.as_slice() {
+            [(a, a_change), (b, b_change)] if a_change + b_change == 0 => {
+                if *a_change < 0 {
+                    (*a, *b, *b_change as usize)
+                } else {
+                    (*b, *a, *a_change as usize)
+                }
+            }
+            _ => return None,
+        };
+
+        let source_depth = from.cards(before).len() - count;
+        let destination_depth = to.cards(after).len() - count;
+
+        Some(Self {
+            cards: to.cards(after)[destination_depth..].to_vec(),
+            from: (0..count)
+                .map(|i| scale.card_position(from, source_depth + i))
+                .collect(),
+            to,
+            destination: (0..count)
+                .map(|i| scale.card_position(to, destination_depth + i))
+                .collect(),
+        })
+    }
+
+    /// Where each moving card sits on screen during a given frame.
+    fn cards_at(&self, frame: u16) -> Vec<(Card, (u16, u16))> {
+        let interpolate = |from: u16, to: u16| {
+            (from as i32 + (to as i32 - from as i32) * frame as i32 / ANIMATION_FRAMES as i32)
+                as u16
+        };
+
+        self.cards
+            .iter()
+            .zip(self.from.iter().zip(self.destination.iter()))
+            .map(|(card, (from, to))| {
+                (
+                    *card,
+                    (interpolate(from.0, to.0), interpolate(from.1, to.1)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Pile {
+    Cell(u8),
+    Foundation(u8),
+    Cascade(u8),
+}
+
+impl Pile {
+    fn all() -> impl Iterator<Item = Pile> {
+        (0..4)
+            .map(Pile::Cell)
+            .chain((0..4).map(Pile::Foundation))
+            .chain((0..8).map(Pile::Cascade))
+    }
+
+    /// The coordinate used to move cards out of this pile, if any can be.
+    fn as_from_coordinate(self) -> Option<FromCoordinate> {
+        match self {
+            Pile::Cell(n) => Some(FromCoordinate::Cell(n)),
+            Pile::Foundation(_) => None,
+            Pile::Cascade(n) => Some(FromCoordinate::Cascade(n)),
+        }
+    }
+
+    fn cards(self, tableau: &Tableau) -> &[Card] {
+        match self {
+            Pile::Cell(n) => tableau.cells[n as usize]
+                .peek()
+                .map_or(&[], std::slice::from_ref),
+            Pile::Foundation(n) => tableau.foundations[n as usize].cards(),
+            Pile::Cascade(n) => tableau.cascades[n as usize].cards(),
+        }
+    }
+
+    /// The character used to refer to this pile when typing a move.
+    fn as_char(self) -> char {
+        match self {
+            Pile::Cell(n) => (b'a' + n) as char,
+            Pile::Foundation(n) => (b'w' + n) as char,
+            Pile::Cascade(n) => (b'1' + n) as char,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum UiScale {
+    Tiny,
+    Small,
+    Medium,
+    Large,
+}
+
+impl UiScale {
+    fn from_terminal_size(size: (u16, u16)) -> Self {
+        let scales = [
+            UiScale::Large,
+            UiScale::Medium,
+            UiScale::Small,
+            UiScale::Tiny,
+        ];
+
+        let scale_x = scales
+            .iter()
+            .find(|scale| size.0 >= scale.get_card_size().0 * 8 + 7)
+            .unwrap_or(&UiScale::Tiny);
+
+        let scale_y = scales
+            .iter()
+            .find(|scale| size.1 >= scale.get_card_size().1 * 2 + 10)
+            .unwrap_or(&UiScale::Tiny);
+
+        *scale_x.min(scale_y)
+    }
+
+    fn get_column_spacing(&self) -> u16 {
+        self.get_card_width() + if self == &UiScale::Large { 2 } else { 1 }
+    }
+
+    fn get_row_spacing(&self) -> u16 {
+        self.get_card_height() + 1
+    }
+
+    fn get_card_width(&self) -> u16 {
+        self.get_card_size().0
+    }
+
+    fn get_card_height(&self) -> u16 {
+        self.get_card_size().1
+    }
+
+    /// The screen position of the top left corner of the card at `depth` in a given pile. Depth is
+    /// only meaningful for cascades, since other piles only ever show their top card.
+    fn card_position(&self, pile: Pile, depth: usize) -> (u16, u16) {
+        match pile {
+            Pile::Cell(n) => (n as u16 * self.get_column_spacing() + 1, 1),
+            Pile::Foundation(n) => ((n as u16 + 4) * self.get_column_spacing() + 1, 1),
+            Pile::Cascade(n) => (
+                n as u16 * self.get_column_spacing() + 1,
+                depth as u16 + self.get_row_spacing() + 1,
+            ),
+        }
+    }
+
+    /// The inverse of `card_position`: which pile, if any, is under a given screen position.
+    fn pile_at(&self, pos: (u16, u16)) -> Option<Pile> {
+        let (col, row) = (pos.0.checked_sub(1)?, pos.1.checked_sub(1)?);
+
+        if col % self.get_column_spacing() >= self.get_card_width() {
+            return None;
+        }
+
+        let n = (col / self.get_column_spacing()) as u8;
+
+        match (n, row) {
+            (0..=3, row) if row < self.get_card_height() => Some(Pile::Cell(n)),
+            (4..=7, row) if row < self.get_card_height() => Some(Pile::Foundation(n - 4)),
+            (0..=7, row) if row >= self.get_row_spacing() => Some(Pile::Cascade(n)),
+            _ => None,
+        }
+    }
+
+    fn get_card_size(&self) -> (u16, u16) {
+        match self {
+            UiScale::Tiny => (3, 2),
+            UiScale::Small => (5, 3),
+            UiScale::Medium => (7, 5),
+            UiScale::Large => (9, 7),
+        }
+    }
+}
+
+struct CardView {
+    scale: UiScale,
+    card: Option<Card>,
+    highlighted: bool,
+    options: DisplayOptions,
+}
+
+impl CardView {
+    fn new(
+        card: Option<&Card>,
+        highlighted: bool,
+        options: DisplayOptions,
+        scale: UiScale,
+    ) -> Self {
+        Self {
+            scale,
+            card: card.cloned(),
+            highlighted,
+            options,
+        }
+    }
+
+    fn char_at(&self, pos: (u16, u16)) -> char {
+        if let Some(card) = self.card {
+            let (suit, rank) = (card.get_suit(), card.get_rank());
+
+            match self.card_char_at(pos) {
+                CardChar::NumberLeft | CardChar::NumberOverflowLeft if rank == Rank::Ten => '1',
+                CardChar::NumberRight | CardChar::NumberOverflowRight if rank == Rank::Ten => '0',
+                CardChar::NumberLeft | CardChar::NumberRight => {
+                    rank.as_str().chars().next().unwrap()
+                }
+                CardChar::Suit if self.options.colours.has_colour() => suit.as_char(),
+                CardChar::Suit => suit.as_monochrome_char(),
+                CardChar::NumberOverflowLeft | CardChar::NumberOverflowRight | CardChar::Blank => {
+                    ' '
+                }
+            }
+        } else if pos.0 == self.scale.get_card_width() / 2
+            && pos.1 == self.scale.get_card_height() / 2
+        {
+            '✖'
+        } else {
+            ' '
+        }
+    }
+
+    fn card_char_at(&self, pos: (u16, u16)) -> CardChar {
+        let card = if let Some(card) = self.card {
+            card
+        } else {
+            return CardChar::Blank;
+        };
+        let (width, height) = self.scale.get_card_size();
+
+        match pos {
+            (0, 0) => CardChar::NumberLeft,
+            (1, 0) => CardChar::NumberOverflowRight,
+            (col, 0) if col == width - 1 => CardChar::Suit,
+            (0, row) if row == height - 1 => CardChar::Suit,
+            _ => {
+                if pos == (width - 2, height - 1) {
+                    CardChar::NumberOverflowLeft
+                } else if pos == (width - 1, height - 1) {
+                    CardChar::NumberRight
+                } else if width >= 6 && height >= 5 {
+                    let (left, center_left, center, center_right, right) = (
+                        1,
+                        width / 3,
+                        (width - 1) / 2,
+                        (width * 2 - 1) / 3,
+                        width - 2,
+                    );
+                    let (top, middle, bottom) = (1, (height - 1) / 2, height - 2);
+                    let (col, row) = pos;
+
+                    if match card.get_rank() {
+                        Rank::Ace => col == center && row == middle,
+                        Rank::Two => col == center && [top, bottom].contains(&row),
+                        Rank::Three => col == center && [top, middle, bottom].contains(&row),
+                        Rank::Four => [left, right].contains(&col) && [top, bottom].contains(&row),
+                        Rank::Five => {
+                            ([left, right].contains(&col) && [top, bottom].contains(&row))
+                                || (col == center && row == middle)
+                        }
+                        Rank::Six => {
+                            [left, center, right].contains(&col) && [top, bottom].contains(&row)
+                        }
+                        Rank::Seven => {
+                            ([left, center, right].contains(&col) && [top, bottom].contains(&row))
+                                || (col == center && row == middle)
+                        }
+                        Rank::Eight => {
+                            [left, center_left, center_right, right].contains(&col)
+                                && [top, bottom].contains(&row)
+                        }
+                        Rank::Nine => {
+                            ([left, center_left, center_right, right].contains(&col)
+                                && [top, bottom].contains(&row))
+                                || (col == center && row == middle)
+                        }
+                        Rank::Ten => {
+                            ([left, center_left, center_right, right].contains(&col)
+                                && [top, bottom].contains(&row))
+                                || ([center_left, center_right].contains(&col) && row == middle)
+                        }
+                        Rank::Jack => {
+                            (row == top && (left..=right).contains(&col))
+                                || (col == center && (top..=bottom).contains(&row))
+                                || (row == bottom && (left..center).contains(&col))
+                        }
+                        Rank::Queen => {
+                            ((left + 1..right).contains(&col) && row == top)
+                                || ((left + 1..=right).contains(&col) && row == bottom)
+                                || ((top + 1..bottom).contains(&row) && col == left)
+                                || ((top + 1..=bottom).contains(&row) && col == right)
+                        }
+                        Rank::King => {
+                            (col == left && (top..=bottom).contains(&row))
+                                || ((left..right).contains(&col) && row == middle)
+                                || (col == right && (top..=bottom).contains(&row) && row != middle)
+                        }
+                    } {
+                        CardChar::Suit
+                    } else {
+                        CardChar::Blank
+                    }
+                } else {
+                    CardChar::Blank
+                }
+            }
+        }
+    }
+
+    fn line_at(&self, row: u16, theme: &Theme) -> String {
+        let line: String = (0..self.scale.get_card_width())
+            .map(|col| self.char_at((col, row)))
+            .collect();
+
+        // Without colour, cards are told apart from the felt with reverse video instead.
+        let style = match (self.card, self.highlighted) {
+            _ if self.options.colours.has_colour() => String::new(),
+            (None, _) => String::new(),
+            (Some(_), false) => style::Invert.to_string(),
+            (Some(_), true) => format!("{}{}", style::Invert, style::Bold),
+        };
+
+        format!(
+            "{}{}{}{}{}",
+            style,
+            if self.card.is_none() {
+                theme.empty_slot_text.fg()
+            } else if self.highlighted {
+                theme.highlighted_text.fg()
+            } else {
+                theme.card_text.fg()
+            },
+            match self
+                .card
+                .map(|card| card.get_suit().colour(self.options.palette))
+            {
+                Some(SuitColour::Black) => theme.black_card.bg(),
+                Some(SuitColour::Red) => theme.red_card.bg(),
+                Some(SuitColour::Blue) => theme.blue_card.bg(),
+                Some(SuitColour::Green) => theme.green_card.bg(),
+                None => theme.empty_slot.bg(),
+            },
+            line,
+            style::Reset,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum CardChar {
+    NumberLeft,
+    NumberRight,
+    NumberOverflowLeft,
+    NumberOverflowRight,
+    Suit,
+    Blank,
+}
+
+fn clear(terminal: &mut impl Write, theme: &Theme) -> io::Result<()> {
+    write!(terminal, "{}", termion::clear::All)?;
+
+    let (width, height) = termion::terminal_size()?;
+
+    for row in 1..=height {
+        write!(terminal, "{}{}", cursor::Goto(1, row), theme.felt.bg())?;
+        write!(terminal, "{}", " ".repeat(width as usize))?;
+    }
+
+    Ok(())
+}
+
+/// Draws the board into a frame buffer, minus the top `hidden.1` cards of pile `hidden.0`, with
+/// any cards that are in motion drawn on top at the given screen positions.
+struct TuiRenderer<'a> {
+    scale: UiScale,
+    theme: &'a Theme,
+    moving: &'a [(Card, (u16, u16))],
+    hidden: Option<(Pile, usize)>,
+}
+
+impl Renderer for TuiRenderer<'_> {
+    type Output = io::Result<Vec<u8>>;
+
+    fn render(&mut self, tableau: &Tableau, view: &View) -> io::Result<Vec<u8>> {
+        let Self {
+            scale,
+            theme,
+            moving,
+            hidden,
+        } = *self;
+        let mut frame = Vec::new();
+
+        let is_marked = |pile: Pile, depth: usize| {
+            pile.as_from_coordinate()
+                .is_some_and(|from| view.is_marked(tableau, from, depth))
+        };
+
+        let visible_cards = |pile: Pile| {
+            let cards = pile.cards(tableau);
+            match hidden {
+                Some((hidden_pile, count)) if hidden_pile == pile => &cards[..cards.len() - count],
+                _ => cards,
+            }
+        };
+
+        clear(&mut frame, theme)?;
+
+        Pile::all().try_for_each(|pile| {
+            let cards = visible_cards(pile);
+
+            if let Pile::Cascade(_) = pile {
+                cards.iter().enumerate().try_for_each(|(depth, card)| {
+                    let card_view =
+                        CardView::new(Some(card), is_marked(pile, depth), view.options, scale);
+                    let pos = scale.card_position(pile, depth);
+                    render_card(&mut frame, &card_view, pos, theme)
+                })
+            } else {
+                let card_view =
+                    CardView::new(cards.last(), is_marked(pile, 0), view.options, scale);
+                let pos = scale.card_position(pile, 0);
+                render_card(&mut frame, &card_view, pos, theme)
+            }
+        })?;
+
+        moving.iter().try_for_each(|(card, pos)| {
+            let card_view = CardView::new(Some(card), false, view.options, scale);
+            render_card(&mut frame, &card_view, *pos, theme)
+        })?;
+
+        Ok(frame)
+    }
+}
+
+fn render_card(
+    terminal: &mut impl Write,
+    card_view: &CardView,
+    pos: (u16, u16),
+    theme: &Theme,
+) -> io::Result<()> {
+    let (col, row) = pos;
+
+    (0..card_view.scale.get_card_height()).try_for_each(|i| {
+        write!(
+            terminal,
+            "{goto}{line}",
+            goto = termion::cursor::Goto(col, i + row),
+            line = card_view.line_at(i, theme)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_leave_piles_alone() {
+        // Key bindings are checked before move input, so they mustn't use any key that's part of
+        // a move, in any language.
+        let piles = (0..4).flat_map(|n| [ToCoordinate::Cell(n), ToCoordinate::Foundation(n)]);
+        for language in Language::ALL {
+            let letters: Vec<char> = piles
+                .clone()
+                .filter_map(|pile| language.pile_letter(pile))
+                .chain('1'..='8')
+                .chain(iter::once('+'))
+                .collect();
+
+            for (keys, command, _) in KEY_BINDINGS {
+                for key in keys.iter() {
+                    if let Key::Char(c) = key {
+                        assert!(
+                            !letters.contains(c),
+                            "{:?} is bound to {:?} but is a pile in {}",
+                            c,
+                            command,
+                            language
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn texts_are_translated() {
+        // The long texts are their own catalog keys, so any edit to one loses its translations
+        // until the catalog is updated to match.
+        let texts = [USAGE, RULES]
+            .into_iter()
+            .chain(MENU.iter().map(|(_, _, description)| *description))
+            .chain(KEY_BINDINGS.iter().map(|(_, _, description)| *description))
+            .chain(GESTURES.iter().map(|(_, description)| *description));
+        for text in texts {
+            for language in Language::ALL {
+                if language != Language::English {
+                    assert_ne!(text, language.tr(text), "not translated into {}", language);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// A terminal that can also save everything written to it as an asciicast v2 recording, which
+/// can be played back with `asciinema play` or any compatible player.
+///
+/// Output is buffered until it's flushed, and each flush becomes one event in the recording, so a
+/// frame always plays back as a whole rather than being split part way through an escape code.
+pub struct Recorder<W: Write> {
+    terminal: W,
+    cast: Option<Cast>,
+}
+
+struct Cast {
+    file: BufWriter<File>,
+    started: Instant,
+    size: (u16, u16),
+    frame: Vec<u8>,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Pass output straight through to the terminal, without recording it.
+    pub fn new(terminal: W) -> Self {
+        Self {
+            terminal,
+            cast: None,
+        }
+    }
+
+    /// Record to the file at `path`, replacing it if it already exists.
+    pub fn to_file(terminal: W, path: &Path) -> io::Result<Self> {
+        let size = termion::terminal_size()?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let mut file = BufWriter::new(File::create(path)?);
+        let term = env::var("TERM").unwrap_or_default();
+        writeln!(file, "{}", header(size, timestamp, &term))?;
+
+        Ok(Self {
+            terminal,
+            cast: Some(Cast {
+                file,
+                started: Instant::now(),
+                size,
+                frame: Vec::new(),
+            }),
+        })
+    }
+}
+
+impl<W: Write> Write for Recorder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.terminal.write(buf)?;
+        if let Some(cast) = &mut self.cast {
+            cast.frame.extend_from_slice(&buf[..written]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.terminal.flush()?;
+
+        let cast = match &mut self.cast {
+            Some(cast) => cast,
+            None => return Ok(()),
+        };
+        let time = cast.started.elapsed().as_secs_f64();
+
+        // Note a resize before the frame that was drawn for the new size.
+        let size = termion::terminal_size()?;
+        if size != cast.size {
+            cast.size = size;
+            let size = format!("{}x{}", size.0, size.1);
+            writeln!(cast.file, "{}", event(time, 'r', &size))?;
+        }
+
+        if !cast.frame.is_empty() {
+            let data = String::from_utf8_lossy(&cast.frame);
+            writeln!(cast.file, "{}", event(time, 'o', &data))?;
+            cast.frame.clear();
+        }
+
+        cast.file.flush()
+    }
+}
+
+/// The first line of a recording, describing the terminal it was made in.
+fn header(size: (u16, u16), timestamp: u64, term: &str) -> String {
+    format!(
+        r#"{{"version":2,"width":{},"height":{},"timestamp":{},"env":{{"TERM":{}}}}}"#,
+        size.0,
+        size.1,
+        timestamp,
+        json_string(term),
+    )
+}
+
+/// One line of a recording: `code` is "o" for output or "r" for a resize, `time` seconds in.
+fn event(time: f64, code: char, data: &str) -> String {
+    format!("[{:.6},\"{}\",{}]", time, code, json_string(data))
+}
+
+/// Quote a string for JSON. Escape codes make up much of a frame, so control characters are
+/// common here.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        assert_eq!(r#""""#, json_string(""));
+        assert_eq!(r#""Deal #1""#, json_string("Deal #1"));
+        assert_eq!(r#""say \"hi\"""#, json_string("say \"hi\""));
+        assert_eq!(r#""C:\\cards""#, json_string("C:\\cards"));
+        assert_eq!(r#""a\nb\r\tc""#, json_string("a\nb\r\tc"));
+        assert_eq!(r#""\u0000\u0007\u007f""#, json_string("\0\x07\x7f"));
+        assert_eq!(r#""10♥ 🂱""#, json_string("10♥ 🂱"));
+    }
+
+    #[test]
+    fn escape_codes() {
+        assert_eq!(
+            r#""\u001b[2J\u001b[1;1H\u001b[38;5;160m♥\u001b[0m""#,
+            json_string("\x1b[2J\x1b[1;1H\x1b[38;5;160m♥\x1b[0m")
+        );
+    }
+
+    #[test]
+    fn header_line() {
+        assert_eq!(
+            r#"{"version":2,"width":120,"height":40,"timestamp":1700000000,"env":{"TERM":"xterm-256color"}}"#,
+            header((120, 40), 1_700_000_000, "xterm-256color")
+        );
+        assert_eq!(
+            r#"{"version":2,"width":80,"height":24,"timestamp":0,"env":{"TERM":""}}"#,
+            header((80, 24), 0, "")
+        );
+    }
+
+    #[test]
+    fn events() {
+        assert_eq!(r#"[1.250000,"o","\u001b[H"]"#, event(1.25, 'o', "\x1b[H"));
+        assert_eq!(r#"[0.000000,"r","100x30"]"#, event(0.0, 'r', "100x30"));
+    }
+
+    #[test]
+    fn passes_through() {
+        let mut recorder = Recorder::new(Vec::new());
+        write!(recorder, "\x1b[2Jhello").unwrap();
+        recorder.flush().unwrap();
+        assert_eq!(b"\x1b[2Jhello", recorder.terminal.as_slice());
+    }
+}
@@ -0,0 +1,421 @@
+use refcell::{config_dir, ColourSupport};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str;
+use termion::color;
+
+/// The colours used to draw every part of the TUI.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub felt: Colour,
+    pub red_card: Colour,
+    pub black_card: Colour,
+    pub blue_card: Colour,
+    pub green_card: Colour,
+    pub card_text: Colour,
+    pub highlighted_text: Colour,
+    pub empty_slot: Colour,
+    pub empty_slot_text: Colour,
+    pub panel: Colour,
+    pub panel_text: Colour,
+}
+
+impl Theme {
+    pub fn classic() -> Self {
+        Self {
+            name: "classic".to_string(),
+            felt: Colour::Ansi(22),
+            red_card: Colour::Ansi(160),
+            black_card: Colour::Ansi(238),
+            blue_card: Colour::Ansi(25),
+            green_card: Colour::Ansi(28),
+            card_text: Colour::Ansi(250),
+            highlighted_text: Colour::Ansi(255),
+            empty_slot: Colour::Ansi(250),
+            empty_slot_text: Colour::Ansi(240),
+            panel: Colour::Ansi(236),
+            panel_text: Colour::Ansi(254),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".to_string(),
+            felt: Colour::Ansi(234),
+            red_card: Colour::Ansi(88),
+            black_card: Colour::Ansi(237),
+            blue_card: Colour::Ansi(18),
+            green_card: Colour::Ansi(22),
+            card_text: Colour::Ansi(248),
+            highlighted_text: Colour::Ansi(255),
+            empty_slot: Colour::Ansi(238),
+            empty_slot_text: Colour::Ansi(243),
+            panel: Colour::Ansi(232),
+            panel_text: Colour::Ansi(250),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_string(),
+            felt: Colour::Ansi(18),
+            red_card: Colour::Ansi(124),
+            black_card: Colour::Ansi(16),
+            blue_card: Colour::Ansi(19),
+            green_card: Colour::Ansi(28),
+            card_text: Colour::Ansi(231),
+            highlighted_text: Colour::Ansi(226),
+            empty_slot: Colour::Ansi(244),
+            empty_slot_text: Colour::Ansi(16),
+            panel: Colour::Ansi(16),
+            panel_text: Colour::Ansi(231),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            name: "light".to_string(),
+            felt: Colour::Ansi(152),
+            red_card: Colour::Ansi(224),
+            black_card: Colour::Ansi(253),
+            blue_card: Colour::Ansi(153),
+            green_card: Colour::Ansi(194),
+            card_text: Colour::Ansi(16),
+            highlighted_text: Colour::Ansi(21),
+            empty_slot: Colour::Ansi(250),
+            empty_slot_text: Colour::Ansi(244),
+            panel: Colour::Ansi(254),
+            panel_text: Colour::Ansi(16),
+        }
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self::classic(),
+            Self::dark(),
+            Self::high_contrast(),
+            Self::light(),
+        ]
+    }
+
+    /// Where the theme config file is read from: `$XDG_CONFIG_HOME/refcell/theme.conf`, falling
+    /// back to `~/.config/refcell/theme.conf`.
+    pub fn config_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("theme.conf"))
+    }
+
+    /// Load the theme config file, if there is one. The file consists of `key = value` lines. The
+    /// `theme` key picks one of the built-in themes to start from, and any other key overrides a
+    /// single colour, given either as a 256-colour palette index or as `#rrggbb`.
+    pub fn load() -> io::Result<Option<Self>> {
+        let path = match Self::config_path() {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(config) => config
+                .parse()
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The same theme, with every colour approximated by one the terminal can show.
+    pub fn for_colours(mut self, colours: ColourSupport) -> Self {
+        for key in KEYS {
+            let colour = self.colour_mut(key).unwrap();
+            *colour = colour.downgrade(colours);
+        }
+        self
+    }
+
+    fn colour_mut(&mut self, key: &str) -> Option<&mut Colour> {
+        Some(match key {
+            "felt" => &mut self.felt,
+            "red_card" => &mut self.red_card,
+            "black_card" => &mut self.black_card,
+            "blue_card" => &mut self.blue_card,
+            "green_card" => &mut self.green_card,
+            "card_text" => &mut self.card_text,
+            "highlighted_text" => &mut self.highlighted_text,
+            "empty_slot" => &mut self.empty_slot,
+            "empty_slot_text" => &mut self.empty_slot_text,
+            "panel" => &mut self.panel,
+            "panel_text" => &mut self.panel_text,
+            _ => return None,
+        })
+    }
+}
+
+const KEYS: &[&str] = &[
+    "felt",
+    "red_card",
+    "black_card",
+    "blue_card",
+    "green_card",
+    "card_text",
+    "highlighted_text",
+    "empty_slot",
+    "empty_slot_text",
+    "panel",
+    "panel_text",
+];
+
+impl str::FromStr for Theme {
+    type Err = String;
+
+    fn from_str(config: &str) -> Result<Self, Self::Err> {
+        let mut theme = Self::classic();
+        let mut overrides = Vec::new();
+
+        for (i, line) in config.lines().enumerate() {
+            let error = |message: String| format!("theme.conf line {}: {}", i + 1, message);
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| error("expected \"key = value\"".to_string()))?;
+
+            if key == "theme" {
+                theme = Self::builtin()
+                    .into_iter()
+                    .find(|builtin| builtin.name == value)
+                    .ok_or_else(|| error(format!("unknown theme {}", value)))?;
+            } else {
+                overrides.push((i, key, value.parse::<Colour>().map_err(error)?));
+            }
+        }
+
+        if !overrides.is_empty() {
+            theme.name = "custom".to_string();
+        }
+
+        for (i, key, colour) in overrides {
+            *theme
+                .colour_mut(key)
+                .ok_or_else(|| format!("theme.conf line {}: unknown key {}", i + 1, key))? = colour;
+        }
+
+        Ok(theme)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Colour {
+    /// The terminal's own foreground or background colour.
+    Default,
+
+    /// One of the 16 standard colours, for terminals that can't show any more.
+    Basic(u8),
+
+    Ansi(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// The RGB values xterm uses for the 16 standard colours.
+const BASIC_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The channel levels of the 6x6x6 colour cube in the 256-colour palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Colour {
+    pub fn fg(self) -> String {
+        match self {
+            Colour::Default => color::Fg(color::Reset).to_string(),
+            Colour::Basic(n) if n < 8 => format!("\x1b[{}m", 30 + n),
+            Colour::Basic(n) => format!("\x1b[{}m", 82 + n),
+            Colour::Ansi(n) => color::Fg(color::AnsiValue(n)).to_string(),
+            Colour::Rgb(r, g, b) => color::Fg(color::Rgb(r, g, b)).to_string(),
+        }
+    }
+
+    pub fn bg(self) -> String {
+        match self {
+            Colour::Default => color::Bg(color::Reset).to_string(),
+            Colour::Basic(n) if n < 8 => format!("\x1b[{}m", 40 + n),
+            Colour::Basic(n) => format!("\x1b[{}m", 92 + n),
+            Colour::Ansi(n) => color::Bg(color::AnsiValue(n)).to_string(),
+            Colour::Rgb(r, g, b) => color::Bg(color::Rgb(r, g, b)).to_string(),
+        }
+    }
+
+    /// The closest colour that can be shown with the given colour support.
+    pub fn downgrade(self, colours: ColourSupport) -> Self {
+        match (self, colours) {
+            (_, colours) if !colours.has_colour() => Colour::Default,
+            (Colour::Default, _) => Colour::Default,
+            (Colour::Rgb(..) | Colour::Ansi(_), ColourSupport::Ansi16) => {
+                let rgb = self.rgb().unwrap();
+                let nearest = (0..16)
+                    .min_by_key(|&n| distance(rgb, BASIC_RGB[n as usize]))
+                    .unwrap();
+                Colour::Basic(nearest)
+            }
+            (Colour::Rgb(r, g, b), ColourSupport::Ansi256) => {
+                let level = |channel: u8| {
+                    (0..6)
+                        .min_by_key(|&i| channel.abs_diff(CUBE_LEVELS[i as usize]))
+                        .unwrap()
+                };
+                Colour::Ansi(16 + 36 * level(r) + 6 * level(g) + level(b))
+            }
+            (colour, _) => colour,
+        }
+    }
+
+    fn rgb(self) -> Option<(u8, u8, u8)> {
+        match self {
+            Colour::Default => None,
+            Colour::Basic(n) => BASIC_RGB.get(n as usize).copied(),
+            Colour::Ansi(n) if n < 16 => BASIC_RGB.get(n as usize).copied(),
+            Colour::Ansi(n) if n < 232 => {
+                let n = n - 16;
+                let level = |i: u8| CUBE_LEVELS[i as usize];
+                Some((level(n / 36), level(n / 6 % 6), level(n % 6)))
+            }
+            Colour::Ansi(n) => {
+                let grey = 8 + 10 * (n - 232);
+                Some((grey, grey, grey))
+            }
+            Colour::Rgb(r, g, b) => Some((r, g, b)),
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    [(a.0, b.0), (a.1, b.1), (a.2, b.2)]
+        .into_iter()
+        .map(|(a, b)| u32::from(a.abs_diff(b)).pow(2))
+        .sum()
+}
+
+impl str::FromStr for Colour {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid colour {}", raw);
+
+        if let Some(hex) = raw.strip_prefix('#') {
+            if hex.len() != 6 || !hex.is_ascii() {
+                return Err(invalid());
+            }
+
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+            Ok(Colour::Rgb(channel(0)?, channel(2)?, channel(4)?))
+        } else {
+            raw.parse().map(Colour::Ansi).map_err(|_| invalid())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colour_from_str() {
+        assert_eq!(Ok(Colour::Rgb(255, 128, 0)), "#ff8000".parse());
+        assert_eq!(Ok(Colour::Rgb(18, 52, 86)), "#123456".parse());
+        assert_eq!(Ok(Colour::Ansi(0)), "0".parse());
+        assert_eq!(Ok(Colour::Ansi(255)), "255".parse());
+
+        for invalid in ["256", "-1", "red", "#12345", "#1234567", "#gg0000", "#ééé"] {
+            assert_eq!(
+                Err(format!("invalid colour {}", invalid)),
+                invalid.parse::<Colour>()
+            );
+        }
+    }
+
+    #[test]
+    fn theme_from_str() {
+        let theme: Theme = "# Mine\ntheme = dark\n\nfelt = #000080\npanel=17\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            Theme {
+                name: "custom".to_string(),
+                felt: Colour::Rgb(0, 0, 128),
+                panel: Colour::Ansi(17),
+                ..Theme::dark()
+            },
+            theme
+        );
+        assert_eq!(Ok(Theme::light()), "theme = light".parse());
+
+        assert_eq!(
+            Err("theme.conf line 2: unknown key felts".to_string()),
+            "theme = dark\nfelts = 22".parse::<Theme>()
+        );
+        assert_eq!(
+            Err("theme.conf line 1: unknown theme neon".to_string()),
+            "theme = neon".parse::<Theme>()
+        );
+        assert_eq!(
+            Err("theme.conf line 1: invalid colour #fff".to_string()),
+            "felt = #fff".parse::<Theme>()
+        );
+        assert_eq!(
+            Err("theme.conf line 1: expected \"key = value\"".to_string()),
+            "felt 22".parse::<Theme>()
+        );
+    }
+
+    #[test]
+    fn downgrade() {
+        let orange = Colour::Rgb(255, 128, 0);
+        assert_eq!(orange, orange.downgrade(ColourSupport::TrueColour));
+        assert_eq!(Colour::Ansi(208), orange.downgrade(ColourSupport::Ansi256));
+        assert_eq!(
+            Colour::Ansi(22),
+            Colour::Ansi(22).downgrade(ColourSupport::Ansi256)
+        );
+
+        // Both RGB and palette colours go to the nearest of the 16 standard colours.
+        assert_eq!(
+            Colour::Basic(9),
+            Colour::Rgb(250, 10, 10).downgrade(ColourSupport::Ansi16)
+        );
+        assert_eq!(
+            Colour::Basic(9),
+            Colour::Ansi(196).downgrade(ColourSupport::Ansi16)
+        );
+        assert_eq!(
+            Colour::Basic(7),
+            Colour::Ansi(255).downgrade(ColourSupport::Ansi16)
+        );
+
+        assert_eq!(Colour::Default, orange.downgrade(ColourSupport::Monochrome));
+        assert_eq!(
+            Colour::Default,
+            Colour::Default.downgrade(ColourSupport::Ansi16)
+        );
+    }
+}
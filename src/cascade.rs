@@ -1,4 +1,4 @@
-use super::{Card, Rank};
+use super::{Card, Rank, Variant};
 use std::fmt;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -18,6 +18,12 @@ impl Cascade {
     }
 
     pub fn try_pop_stack(&mut self, count: usize) -> Option<Vec<Card>> {
+        self.try_pop_stack_in(count, Variant::FreeCell)
+    }
+
+    /// Take the top `count` cards, as long as they form a stack that can be moved as one under
+    /// the rules of `variant`.
+    pub fn try_pop_stack_in(&mut self, count: usize, variant: Variant) -> Option<Vec<Card>> {
         match count {
             0 => Some(Vec::new()),
             1 => self.pop().map(|card| vec![card]),
@@ -27,7 +33,7 @@ impl Cascade {
                 let mut prev_card = card_iter.next().unwrap();
 
                 while let Some(card) = card_iter.next() {
-                    if !prev_card.is_legal(card) {
+                    if !variant.is_legal(prev_card, card) {
                         return None;
                     }
 
@@ -44,17 +50,35 @@ impl Cascade {
     }
 
     pub fn card_is_legal(&self, card: &Card) -> bool {
+        self.card_is_legal_in(card, Variant::FreeCell)
+    }
+
+    pub fn card_is_legal_in(&self, card: &Card, variant: Variant) -> bool {
         self.0
             .last()
-            .map_or(true, |last_card| last_card.is_legal(card))
+            .map_or(true, |last_card| variant.is_legal(last_card, card))
     }
 
     pub fn stack_is_legal(&self, stack: &[Card]) -> bool {
-        stack.first().map_or(true, |card| self.card_is_legal(card))
+        self.stack_is_legal_in(stack, Variant::FreeCell)
+    }
+
+    pub fn stack_is_legal_in(&self, stack: &[Card], variant: Variant) -> bool {
+        stack
+            .first()
+            .map_or(true, |card| self.card_is_legal_in(card, variant))
     }
 
     pub fn try_push(&mut self, card: Card) -> Result<(), (Card, &'static str)> {
-        if self.card_is_legal(&card) {
+        self.try_push_in(card, Variant::FreeCell)
+    }
+
+    pub fn try_push_in(
+        &mut self,
+        card: Card,
+        variant: Variant,
+    ) -> Result<(), (Card, &'static str)> {
+        if self.card_is_legal_in(&card, variant) {
             self.push(card);
             Ok(())
         } else {
@@ -67,7 +91,15 @@ impl Cascade {
     }
 
     pub fn try_push_stack(&mut self, stack: Vec<Card>) -> Result<(), (Vec<Card>, &'static str)> {
-        if self.stack_is_legal(&stack) {
+        self.try_push_stack_in(stack, Variant::FreeCell)
+    }
+
+    pub fn try_push_stack_in(
+        &mut self,
+        stack: Vec<Card>,
+        variant: Variant,
+    ) -> Result<(), (Vec<Card>, &'static str)> {
+        if self.stack_is_legal_in(&stack, variant) {
             self.push_stack(stack);
             Ok(())
         } else {
@@ -117,7 +149,7 @@ impl fmt::Display for Cascade {
 #[cfg(test)]
 mod tests {
     use super::super::Suit;
-    use super::{Card, Cascade, Rank};
+    use super::{Card, Cascade, Rank, Variant};
 
     #[test]
     fn new() {
@@ -215,6 +247,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn try_push_bakers_game() {
+        let mut cascade = Cascade::new(vec![Card::new(Rank::King, Suit::Clubs)]);
+
+        assert!(
+            !cascade.card_is_legal_in(&Card::new(Rank::Queen, Suit::Hearts), Variant::BakersGame)
+        );
+        assert_eq!(
+            Ok(()),
+            cascade.try_push_in(Card::new(Rank::Queen, Suit::Clubs), Variant::BakersGame),
+        );
+    }
+
     #[test]
     fn push() {
         let mut cascade = Cascade::new(vec![Card::new(Rank::King, Suit::Clubs)]);
@@ -44,7 +44,7 @@ impl fmt::Display for Cell {
         if let Some(card) = self.peek() {
             write!(f, "{}", card)
         } else {
-            write!(f, "\x1b[2m🂠\x1b[0m ")
+            write!(f, "🂠 ")
         }
     }
 }
@@ -0,0 +1,164 @@
+use super::{Game, Language, Suit, Tableau};
+use std::time::Duration;
+
+/// A moment in a game worth timing, for comparing runs of the same deal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Split {
+    /// The first card went up to a foundation.
+    FirstFoundation,
+
+    /// A foundation was built up to the king.
+    Suit(Suit),
+
+    /// The game was won.
+    Won,
+}
+
+impl Split {
+    /// The split in words, in the player's language.
+    pub fn describe(self, language: Language) -> String {
+        match self {
+            Self::FirstFoundation => language.tr("First foundation card").to_string(),
+            Self::Suit(suit) => language.format("Suit complete: {}", &[&language.suit_name(suit)]),
+            Self::Won => language.tr("Won").to_string(),
+        }
+    }
+}
+
+/// A time as minutes, seconds and tenths, such as "2:05.3".
+pub fn format_time(time: Duration) -> String {
+    let tenths = time.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+/// The lines for `Game::describe_times`.
+pub fn describe(game: &Game, language: Language) -> Vec<String> {
+    let mut lines = vec![language.format("Time: {}", &[&format_time(game.elapsed())])];
+    if let Some(&time) = game.move_times().last() {
+        lines.push(language.format("Last move: {}", &[&format_time(time)]));
+    }
+    lines.extend(game.splits().into_iter().map(|(split, time)| {
+        language.format("{} at {}", &[&split.describe(language), &format_time(time)])
+    }));
+    lines
+}
+
+/// Every split the game has reached, found by going through the positions after each move.
+pub fn splits(game: &Game) -> Vec<(Split, Duration)> {
+    let mut splits = Vec::new();
+    let reached = |split: &Split, tableau: &Tableau| match split {
+        Split::FirstFoundation => tableau
+            .foundations
+            .iter()
+            .any(|foundation| !foundation.cards().is_empty()),
+        Split::Suit(suit) => tableau.foundations.iter().any(|foundation| {
+            foundation.cards().len() == 13 && foundation.cards()[0].get_suit() == *suit
+        }),
+        Split::Won => tableau.is_won(),
+    };
+
+    for (tableau, &time) in game.history()[1..].iter().zip(game.times()) {
+        let suits = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades].map(Split::Suit);
+        for split in [Split::FirstFoundation, Split::Won].iter().chain(&suits) {
+            let is_new = !splits.iter().any(|(reached, _)| reached == split);
+            if is_new && reached(split, tableau) {
+                splits.push((*split, time));
+            }
+        }
+    }
+
+    splits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, Deck};
+
+    #[test]
+    fn times() {
+        let mut game = Game::deal(Deck::numbered(1));
+        assert_eq!(Duration::ZERO, game.elapsed());
+
+        for action in ["6a", "6b", "6w", "6x"] {
+            game.action(action.parse().unwrap()).unwrap();
+        }
+        assert_eq!(4, game.times().len());
+        assert_eq!(Duration::ZERO, game.move_times()[0]);
+        assert!(game.elapsed() >= game.times()[3]);
+
+        game.action(Action::Undo).unwrap();
+        assert_eq!(3, game.move_times().len());
+
+        game.restart();
+        assert!(game.times().is_empty());
+        assert_eq!(Duration::ZERO, game.elapsed());
+    }
+
+    #[test]
+    fn splits() {
+        let mut game = Game::deal(Deck::numbered(1));
+        for action in ["6a", "6b", "6w"] {
+            game.action(action.parse().unwrap()).unwrap();
+        }
+
+        assert_eq!(
+            vec![(Split::FirstFoundation, game.times()[2])],
+            game.splits()
+        );
+    }
+
+    #[test]
+    fn won() {
+        let game = Game::load(
+            "# RefCell saved game
+            variant: freecell
+            w: AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC
+            x: AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD
+            y: AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH
+            z: AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS
+            1: QS KC
+            2: KD
+            3: KH
+            4: KS
+            times: 0.0 12.5 14.0
+            moves: 1a 1z 4z",
+        )
+        .unwrap();
+
+        // The first move won the game, so the clock stopped there, but the splits carry on.
+        assert_eq!(Duration::ZERO, game.elapsed());
+        assert_eq!(
+            vec![
+                (Split::FirstFoundation, Duration::ZERO),
+                (Split::Won, Duration::ZERO),
+                (Split::Suit(Suit::Spades), Duration::from_secs(14)),
+            ],
+            game.splits()
+        );
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!("0:00.0", format_time(Duration::ZERO));
+        assert_eq!("2:05.3", format_time(Duration::from_millis(125_399)));
+        assert_eq!(
+            "Suit complete: hearts",
+            Split::Suit(Suit::Hearts).describe(Language::English)
+        );
+    }
+
+    #[test]
+    fn describe() {
+        let mut game = Game::deal(Deck::numbered(1));
+        assert_eq!(vec!["Time: 0:00.0"], game.describe_times(Language::English));
+
+        game.action("6a".parse().unwrap()).unwrap();
+        game.action("6b".parse().unwrap()).unwrap();
+        game.action("6w".parse().unwrap()).unwrap();
+        let lines = game.describe_times(Language::French);
+        assert_eq!(3, lines.len());
+        assert!(lines[0].starts_with("Temps : 0:00."));
+        assert!(lines[2].starts_with("Première carte en fondation à 0:00."));
+    }
+}
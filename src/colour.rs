@@ -0,0 +1,159 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use std::str;
+use std::sync::OnceLock;
+
+/// How much colour the output device can show, from nothing at all up to 24-bit colour.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+pub enum ColourSupport {
+    /// No escape codes at all, for pipes, log files and dumb terminals.
+    Plain,
+
+    /// Text attributes such as bold and reverse video, but no colour, as requested by `NO_COLOR`.
+    Monochrome,
+
+    /// The 16 standard ANSI colours.
+    #[default]
+    Ansi16,
+
+    /// The 256-colour xterm palette.
+    Ansi256,
+
+    /// 24-bit RGB colour.
+    TrueColour,
+}
+
+impl ColourSupport {
+    /// Work out what standard output supports from the environment. The result is cached, since
+    /// neither the environment nor standard output is expected to change while the game runs.
+    pub fn detect() -> Self {
+        static DETECTED: OnceLock<ColourSupport> = OnceLock::new();
+
+        *DETECTED.get_or_init(|| {
+            let var = |name| env::var(name).ok();
+
+            Self::from_env(
+                io::stdout().is_terminal(),
+                var("NO_COLOR").as_deref(),
+                var("TERM").as_deref(),
+                var("COLORTERM").as_deref(),
+            )
+        })
+    }
+
+    /// The logic behind `detect()`, given whether the output is a terminal and the values of the
+    /// `NO_COLOR`, `TERM` and `COLORTERM` environment variables.
+    pub fn from_env(
+        is_terminal: bool,
+        no_color: Option<&str>,
+        term: Option<&str>,
+        colorterm: Option<&str>,
+    ) -> Self {
+        let term = term.unwrap_or_default();
+
+        if !is_terminal || term == "dumb" {
+            Self::Plain
+        } else if no_color.is_some_and(|value| !value.is_empty()) {
+            Self::Monochrome
+        } else if matches!(colorterm, Some("truecolor" | "24bit")) {
+            Self::TrueColour
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else if term.is_empty() {
+            Self::Monochrome
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// Whether escape codes can be written at all.
+    pub fn has_escapes(self) -> bool {
+        self >= Self::Monochrome
+    }
+
+    /// Whether cards can be told apart by colour, or need text markers instead.
+    pub fn has_colour(self) -> bool {
+        self >= Self::Ansi16
+    }
+}
+
+/// Parses the names used to force a colour mode on the command line.
+impl str::FromStr for ColourSupport {
+    type Err = &'static str;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim() {
+            "plain" => Ok(Self::Plain),
+            "monochrome" => Ok(Self::Monochrome),
+            "16" => Ok(Self::Ansi16),
+            "256" => Ok(Self::Ansi256),
+            "truecolour" | "truecolor" => Ok(Self::TrueColour),
+            _ => Err("Unknown colour mode."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColourSupport;
+
+    #[test]
+    fn from_env() {
+        let xterm = Some("xterm");
+
+        assert_eq!(
+            ColourSupport::Plain,
+            ColourSupport::from_env(false, None, xterm, Some("truecolor")),
+        );
+        assert_eq!(
+            ColourSupport::Plain,
+            ColourSupport::from_env(true, None, Some("dumb"), None),
+        );
+        assert_eq!(
+            ColourSupport::Monochrome,
+            ColourSupport::from_env(true, Some("1"), xterm, Some("truecolor")),
+        );
+        assert_eq!(
+            ColourSupport::Monochrome,
+            ColourSupport::from_env(true, None, None, None),
+        );
+        assert_eq!(
+            ColourSupport::TrueColour,
+            ColourSupport::from_env(true, Some(""), xterm, Some("24bit")),
+        );
+        assert_eq!(
+            ColourSupport::Ansi256,
+            ColourSupport::from_env(true, None, Some("xterm-256color"), None),
+        );
+        assert_eq!(
+            ColourSupport::Ansi16,
+            ColourSupport::from_env(true, None, xterm, None),
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(ColourSupport::Plain), "plain".parse());
+        assert_eq!(Ok(ColourSupport::Ansi256), "256".parse());
+        assert_eq!(Ok(ColourSupport::TrueColour), "truecolor".parse());
+        assert_eq!(
+            Err("Unknown colour mode."),
+            "rainbow".parse::<ColourSupport>()
+        );
+    }
+
+    #[test]
+    fn has_escapes() {
+        assert!(!ColourSupport::Plain.has_escapes());
+        assert!(ColourSupport::Monochrome.has_escapes());
+        assert!(ColourSupport::TrueColour.has_escapes());
+    }
+
+    #[test]
+    fn has_colour() {
+        assert!(!ColourSupport::Plain.has_colour());
+        assert!(!ColourSupport::Monochrome.has_colour());
+        assert!(ColourSupport::Ansi16.has_colour());
+        assert!(ColourSupport::TrueColour.has_colour());
+    }
+}
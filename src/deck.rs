@@ -1,7 +1,11 @@
+use super::ColourSupport;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, SeedableRng};
+use std::env;
 use std::fmt;
 use std::iter;
+use std::str;
 
 #[derive(Debug)]
 pub struct Deck(Vec<Card>);
@@ -31,12 +35,47 @@ impl Deck {
         )
     }
 
+    /// Deal number `number` in the sequence used by Microsoft FreeCell, so that well-known deals
+    /// can be played and compared. Any number can be used as a seed for a reproducible deal.
+    pub fn numbered(number: u32) -> Self {
+        let mut seed = number;
+        let mut rand = || {
+            seed = seed.wrapping_mul(214013).wrapping_add(2531011) & 0x7fff_ffff;
+            seed >> 16
+        };
+
+        // Ace of clubs, ace of diamonds, ace of hearts, ace of spades, two of clubs...
+        let mut cards: Vec<Card> = (1..=13)
+            .flat_map(|rank: u8| {
+                [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades]
+                    .into_iter()
+                    .map(move |suit| Card::new(rank.try_into().unwrap(), suit))
+            })
+            .collect();
+
+        let mut dealt: Vec<Card> = (0..52)
+            .map(|i| cards.swap_remove(rand() as usize % (52 - i)))
+            .collect();
+
+        // Cards are dealt from the end of the deck.
+        dealt.reverse();
+        Self::new(dealt)
+    }
+
     pub fn shuffled() -> Self {
         let mut deck = Self::fresh();
         deck.shuffle();
         deck
     }
 
+    /// A shuffled deck that's the same every time for a given seed. Unlike `numbered`, the order
+    /// depends on the random number generator, so it may change between versions of the game.
+    pub fn seeded(seed: u64) -> Self {
+        let mut deck = Self::fresh();
+        deck.0.shuffle(&mut StdRng::seed_from_u64(seed));
+        deck
+    }
+
     pub fn shuffle(&mut self) {
         let mut rng = thread_rng();
         self.0.shuffle(&mut rng);
@@ -49,7 +88,8 @@ impl Deck {
 
 #[cfg(test)]
 mod test_deck {
-    use super::{Card, Deck, Suit};
+    use super::{Card, Deck, Rank, Suit};
+    use std::iter;
 
     #[test]
     fn fresh_pop() {
@@ -73,6 +113,44 @@ mod test_deck {
         assert_eq!(None, deck.pop());
     }
 
+    #[test]
+    fn numbered() {
+        let mut deck = Deck::numbered(1);
+
+        // The first row of the well-known deal #1
+        assert_eq!(Some(Card(Rank::Jack, Suit::Diamonds)), deck.pop());
+        assert_eq!(Some(Card(Rank::Two, Suit::Diamonds)), deck.pop());
+        assert_eq!(Some(Card(Rank::Nine, Suit::Hearts)), deck.pop());
+        assert_eq!(Some(Card(Rank::Jack, Suit::Clubs)), deck.pop());
+        assert_eq!(Some(Card(Rank::Five, Suit::Diamonds)), deck.pop());
+        assert_eq!(Some(Card(Rank::Seven, Suit::Hearts)), deck.pop());
+        assert_eq!(Some(Card(Rank::Seven, Suit::Clubs)), deck.pop());
+        assert_eq!(Some(Card(Rank::Five, Suit::Hearts)), deck.pop());
+
+        assert_eq!(44, iter::from_fn(|| deck.pop()).count());
+
+        assert_eq!(
+            format!("{:?}", Deck::numbered(11982)),
+            format!("{:?}", Deck::numbered(11982)),
+        );
+        assert_ne!(
+            format!("{:?}", Deck::numbered(1)),
+            format!("{:?}", Deck::numbered(2)),
+        );
+    }
+
+    #[test]
+    fn seeded() {
+        assert_eq!(
+            format!("{:?}", Deck::seeded(7)),
+            format!("{:?}", Deck::seeded(7)),
+        );
+        assert_ne!(
+            format!("{:?}", Deck::seeded(7)),
+            format!("{:?}", Deck::seeded(8)),
+        );
+    }
+
     #[test]
     fn shuffled() {
         let deck = Deck::shuffled();
@@ -106,27 +184,134 @@ impl Card {
         self.get_suit().is_red() != other.get_suit().is_red()
             && self.get_rank().try_decrement() == Some(other.get_rank())
     }
+
+    /// The two-line face used by `Display`, in a given palette. A highlighted card is drawn
+    /// without reverse video so that it stands out from its neighbours. Without colour, red suits
+    /// are drawn in outline instead, and without escape codes highlighting isn't shown at all.
+    pub fn face(&self, palette: Palette, colours: ColourSupport, highlighted: bool) -> String {
+        let suit = if colours.has_colour() {
+            self.get_suit().as_char()
+        } else {
+            self.get_suit().as_monochrome_char()
+        };
+        let space = if self.get_rank() == Rank::Ten {
+            ""
+        } else {
+            " "
+        };
+        let lines = [
+            format!("{}{}{}", self.get_rank(), space, suit),
+            format!("{}{}{}", suit, space, self.get_rank()),
+        ];
+
+        if !colours.has_escapes() {
+            return lines.join("\n");
+        }
+
+        let attributes = if highlighted { "1" } else { "7" };
+        let codes = if colours.has_colour() {
+            let color = match self.get_suit().colour(palette) {
+                SuitColour::Black => "39",
+                SuitColour::Red => "91",
+                SuitColour::Blue => "94",
+                SuitColour::Green => "92",
+            };
+            format!("{};{}", color, attributes)
+        } else {
+            attributes.to_string()
+        };
+
+        lines
+            .iter()
+            .map(|line| format!("\x1b[{}m{}\x1b[0m", codes, line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The card's glyph from the Unicode Playing Cards block. The block includes a Knight between
+    /// the Jack and the Queen for tarot decks, which a standard deck skips over.
+    pub fn glyph(&self) -> char {
+        let rank = u8::from(self.get_rank());
+        let offset = if self.get_rank() < Rank::Queen {
+            rank
+        } else {
+            rank + 1
+        };
+        let suit = match self.get_suit() {
+            Suit::Spades => 0x00,
+            Suit::Hearts => 0x10,
+            Suit::Diamonds => 0x20,
+            Suit::Clubs => 0x30,
+        };
+
+        char::from_u32(0x1f0a0 + suit + u32::from(offset)).unwrap()
+    }
+
+    /// A compact one-glyph face, for fitting a whole tableau into a narrow terminal. A highlighted
+    /// card is drawn in reverse video.
+    pub fn glyph_face(
+        &self,
+        palette: Palette,
+        colours: ColourSupport,
+        highlighted: bool,
+    ) -> String {
+        let mut codes = Vec::new();
+
+        if colours.has_colour() {
+            codes.push(match self.get_suit().colour(palette) {
+                SuitColour::Black => "39",
+                SuitColour::Red => "91",
+                SuitColour::Blue => "94",
+                SuitColour::Green => "92",
+            });
+        }
+
+        if highlighted {
+            codes.push("7");
+        }
+
+        if colours.has_escapes() && !codes.is_empty() {
+            format!("\x1b[{}m{}\x1b[0m", codes.join(";"), self.glyph())
+        } else {
+            self.glyph().to_string()
+        }
+    }
 }
 
+/// The face without any escape codes, the same wherever it's written. Use `Card::face` for colour.
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "\
-\x1b[{color};7m{rank}{space}{suit}\x1b[0m
-\x1b[{color};7m{suit}{space}{rank}\x1b[0m",
-            color = if self.get_suit().is_red() { "91" } else { "39" },
-            rank = self.get_rank(),
-            space = if self.get_rank() == Rank::Ten {
-                ""
-            } else {
-                " "
-            },
-            suit = self.get_suit(),
+            "{}",
+            self.face(Palette::default(), ColourSupport::Plain, false)
         )
     }
 }
 
+/// Parses the two-character form used in saved games, such as "JD" or "TH". "10H" is accepted
+/// too, and case doesn't matter.
+impl str::FromStr for Card {
+    type Err = &'static str;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim().to_ascii_uppercase();
+        let input = match input.strip_prefix("10") {
+            Some(suit) => format!("T{}", suit),
+            None => input,
+        };
+
+        let mut chars = input.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(rank), Some(suit), None) => Rank::from_char(rank)
+                .zip(Suit::from_letter(suit))
+                .map(|(rank, suit)| Card::new(rank, suit))
+                .ok_or("Invalid card."),
+            _ => Err("Invalid card."),
+        }
+    }
+}
+
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub enum Rank {
@@ -164,6 +349,32 @@ impl Rank {
         }
     }
 
+    /// The rank in words for the court cards and aces, and in figures for the rest.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ace => "Ace",
+            Self::Jack => "Jack",
+            Self::Queen => "Queen",
+            Self::King => "King",
+            rank => rank.as_str(),
+        }
+    }
+
+    /// A single character for the rank, with `T` standing in for ten.
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Ten => 'T',
+            rank => rank.as_str().chars().next().unwrap(),
+        }
+    }
+
+    /// The rank written as `as_char` writes it, in either case.
+    pub fn from_char(c: char) -> Option<Rank> {
+        (1..=13)
+            .filter_map(|i| Rank::try_from(i).ok())
+            .find(|rank| rank.as_char() == c.to_ascii_uppercase())
+    }
+
     pub fn try_increment(&self) -> Option<Rank> {
         (*self as u8 + 1).try_into().ok()
     }
@@ -208,36 +419,9 @@ impl From<Rank> for u8 {
     }
 }
 
-/*
-impl fmt::Display for Card {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "\x1b[{}m{}\x1b[0m ",
-            if self.get_suit().is_red() { "91" } else { "39" },
-            char::from_u32(
-                0x1f0a0
-                    + if self.get_rank() < 12 {
-                        self.get_rank()
-                    } else {
-                        self.get_rank() + 1
-                    } as u32
-                    + match self.get_suit() {
-                        Suit::Spades => 0x00,
-                        Suit::Hearts => 0x10,
-                        Suit::Diamonds => 0x20,
-                        Suit::Clubs => 0x30,
-                    }
-            )
-            .unwrap()
-        )
-    }
-}
-*/
-
 #[cfg(test)]
 mod test_card {
-    use super::{Card, Rank, Suit};
+    use super::{Card, ColourSupport, Palette, Rank, Suit};
 
     #[test]
     fn new_valid() {
@@ -247,6 +431,77 @@ mod test_card {
         );
     }
 
+    #[test]
+    fn face() {
+        let card = Card::new(Rank::Ten, Suit::Diamonds);
+        let colours = ColourSupport::Ansi16;
+
+        assert_eq!(
+            "\x1b[91;7m10\u{2666}\x1b[0m\n\x1b[91;7m\u{2666}10\x1b[0m",
+            card.face(Palette::TwoColour, colours, false),
+        );
+        assert_eq!(
+            "\x1b[94;7m10\u{2666}\x1b[0m\n\x1b[94;7m\u{2666}10\x1b[0m",
+            card.face(Palette::FourColour, colours, false),
+        );
+        assert_eq!(
+            "\x1b[91;1m10\u{2666}\x1b[0m\n\x1b[91;1m\u{2666}10\x1b[0m",
+            card.face(Palette::TwoColour, colours, true),
+        );
+        assert_eq!(
+            "\x1b[1m10\u{2662}\x1b[0m\n\x1b[1m\u{2662}10\x1b[0m",
+            card.face(Palette::FourColour, ColourSupport::Monochrome, true),
+        );
+        assert_eq!(
+            "A \u{2660}\n\u{2660} A",
+            Card::new(Rank::Ace, Suit::Spades).face(Palette::TwoColour, ColourSupport::Plain, true),
+        );
+    }
+
+    #[test]
+    fn glyph() {
+        assert_eq!('\u{1f0a1}', Card::new(Rank::Ace, Suit::Spades).glyph());
+        assert_eq!('\u{1f0ba}', Card::new(Rank::Ten, Suit::Hearts).glyph());
+        assert_eq!('\u{1f0cb}', Card::new(Rank::Jack, Suit::Diamonds).glyph());
+        assert_eq!('\u{1f0dd}', Card::new(Rank::Queen, Suit::Clubs).glyph());
+        assert_eq!('\u{1f0ae}', Card::new(Rank::King, Suit::Spades).glyph());
+    }
+
+    #[test]
+    fn glyph_face() {
+        let card = Card::new(Rank::Ace, Suit::Diamonds);
+
+        assert_eq!(
+            "\x1b[91m\u{1f0c1}\x1b[0m",
+            card.glyph_face(Palette::TwoColour, ColourSupport::Ansi16, false),
+        );
+        assert_eq!(
+            "\x1b[94;7m\u{1f0c1}\x1b[0m",
+            card.glyph_face(Palette::FourColour, ColourSupport::Ansi16, true),
+        );
+        assert_eq!(
+            "\x1b[7m\u{1f0c1}\x1b[0m",
+            card.glyph_face(Palette::TwoColour, ColourSupport::Monochrome, true),
+        );
+        assert_eq!(
+            "\u{1f0c1}",
+            card.glyph_face(Palette::TwoColour, ColourSupport::Plain, true),
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Card(Rank::Jack, Suit::Diamonds)), "JD".parse());
+        assert_eq!(Ok(Card(Rank::Ten, Suit::Hearts)), "th".parse());
+        assert_eq!(Ok(Card(Rank::Ten, Suit::Hearts)), "10H".parse());
+        assert_eq!(Ok(Card(Rank::Ace, Suit::Spades)), " AS\n".parse());
+        assert_eq!(Err("Invalid card."), "1S".parse::<Card>());
+        assert_eq!(Err("Invalid card."), "AX".parse::<Card>());
+        assert_eq!(Err("Invalid card."), "ASS".parse::<Card>());
+        assert_eq!(Err("Invalid card."), "".parse::<Card>());
+        assert_eq!(Err("Invalid card."), "\u{2660}A".parse::<Card>());
+    }
+
     #[test]
     fn eq() {
         assert_eq!(Card(Rank::Ace, Suit::Hearts), Card(Rank::Ace, Suit::Hearts));
@@ -279,6 +534,56 @@ impl Suit {
             Self::Diamonds => '\u{2666}',
         }
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Clubs => "clubs",
+            Self::Diamonds => "diamonds",
+            Self::Hearts => "hearts",
+            Self::Spades => "spades",
+        }
+    }
+
+    /// The initial of the suit, for output that has to stick to ASCII.
+    pub fn as_letter(&self) -> char {
+        match self {
+            Self::Clubs => 'C',
+            Self::Diamonds => 'D',
+            Self::Hearts => 'H',
+            Self::Spades => 'S',
+        }
+    }
+
+    /// The suit with the given initial, in either case.
+    pub fn from_letter(c: char) -> Option<Suit> {
+        [Self::Clubs, Self::Diamonds, Self::Hearts, Self::Spades]
+            .into_iter()
+            .find(|suit| suit.as_letter() == c.to_ascii_uppercase())
+    }
+
+    /// The suit symbol for output without colour: black suits are filled and red suits are drawn
+    /// in outline, so that the two can still be told apart.
+    pub fn as_monochrome_char(&self) -> char {
+        match self {
+            Self::Spades => '\u{2660}',
+            Self::Clubs => '\u{2663}',
+            Self::Hearts => '\u{2661}',
+            Self::Diamonds => '\u{2662}',
+        }
+    }
+
+    /// The colour the suit is drawn in. The traditional two colours are all that's needed to
+    /// play, but a four-colour deck is easier to read at a glance.
+    pub fn colour(&self, palette: Palette) -> SuitColour {
+        match (palette, self) {
+            (_, Self::Spades) => SuitColour::Black,
+            (_, Self::Hearts) => SuitColour::Red,
+            (Palette::TwoColour, Self::Clubs) => SuitColour::Black,
+            (Palette::TwoColour, Self::Diamonds) => SuitColour::Red,
+            (Palette::FourColour, Self::Clubs) => SuitColour::Green,
+            (Palette::FourColour, Self::Diamonds) => SuitColour::Blue,
+        }
+    }
 }
 
 impl fmt::Display for Suit {
@@ -287,9 +592,66 @@ impl fmt::Display for Suit {
     }
 }
 
+/// Whether cards are written out as text or drawn with their Unicode playing card glyphs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CardStyle {
+    #[default]
+    Text,
+    Glyph,
+}
+
+impl CardStyle {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Text => Self::Glyph,
+            Self::Glyph => Self::Text,
+        }
+    }
+
+    /// Whether the locale, as set by `LC_ALL`, `LC_CTYPE` or `LANG`, can carry this style, which
+    /// for glyphs means it names UTF-8. Only the locale is checked, not the terminal or its font.
+    pub fn fits_locale(self) -> bool {
+        match self {
+            Self::Text => true,
+            Self::Glyph => ["LC_ALL", "LC_CTYPE", "LANG"]
+                .into_iter()
+                .filter_map(|name| env::var(name).ok())
+                .find(|value| !value.is_empty())
+                .is_some_and(|value| {
+                    let value = value.to_ascii_lowercase();
+                    value.contains("utf-8") || value.contains("utf8")
+                }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Palette {
+    #[default]
+    TwoColour,
+    FourColour,
+}
+
+impl Palette {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::TwoColour => Self::FourColour,
+            Self::FourColour => Self::TwoColour,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SuitColour {
+    Black,
+    Red,
+    Blue,
+    Green,
+}
+
 #[cfg(test)]
 mod test_suit {
-    use super::Suit;
+    use super::{Palette, Suit, SuitColour};
 
     #[test]
     fn is_red() {
@@ -298,4 +660,42 @@ mod test_suit {
         assert_eq!(true, Suit::Diamonds.is_red());
         assert_eq!(true, Suit::Hearts.is_red());
     }
+
+    #[test]
+    fn as_letter() {
+        assert_eq!('C', Suit::Clubs.as_letter());
+        assert_eq!('D', Suit::Diamonds.as_letter());
+        assert_eq!('H', Suit::Hearts.as_letter());
+        assert_eq!('S', Suit::Spades.as_letter());
+    }
+
+    #[test]
+    fn from_letter() {
+        assert_eq!(Some(Suit::Clubs), Suit::from_letter('C'));
+        assert_eq!(Some(Suit::Diamonds), Suit::from_letter('d'));
+        assert_eq!(None, Suit::from_letter('X'));
+    }
+
+    #[test]
+    fn as_monochrome_char() {
+        assert_eq!('\u{2663}', Suit::Clubs.as_monochrome_char());
+        assert_eq!('\u{2662}', Suit::Diamonds.as_monochrome_char());
+        assert_eq!('\u{2661}', Suit::Hearts.as_monochrome_char());
+        assert_eq!('\u{2660}', Suit::Spades.as_monochrome_char());
+    }
+
+    #[test]
+    fn colour() {
+        let two = Palette::TwoColour;
+        assert_eq!(SuitColour::Black, Suit::Clubs.colour(two));
+        assert_eq!(SuitColour::Red, Suit::Diamonds.colour(two));
+        assert_eq!(SuitColour::Red, Suit::Hearts.colour(two));
+        assert_eq!(SuitColour::Black, Suit::Spades.colour(two));
+
+        let four = Palette::FourColour;
+        assert_eq!(SuitColour::Green, Suit::Clubs.colour(four));
+        assert_eq!(SuitColour::Blue, Suit::Diamonds.colour(four));
+        assert_eq!(SuitColour::Red, Suit::Hearts.colour(four));
+        assert_eq!(SuitColour::Black, Suit::Spades.colour(four));
+    }
 }
@@ -0,0 +1,22 @@
+use std::env;
+use std::path::PathBuf;
+
+/// The directory that refcell reads its config files from: `$XDG_CONFIG_HOME/refcell`, falling
+/// back to `~/.config/refcell`.
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("refcell"))
+}
+
+/// The directory that refcell keeps its data in: `$XDG_DATA_HOME/refcell`, falling back to
+/// `~/.local/share/refcell`.
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("refcell"))
+}
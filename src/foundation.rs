@@ -36,6 +36,10 @@ impl Foundation {
             Err((card, "That card is not valid on that foundation."))
         }
     }
+
+    pub fn cards(&self) -> &Vec<Card> {
+        &self.0
+    }
 }
 
 impl Single for Foundation {
@@ -53,7 +57,7 @@ impl fmt::Display for Foundation {
         if let Some(card) = self.peek() {
             write!(f, "{}", card)
         } else {
-            write!(f, "\x1b[2m🂠\x1b[0m ")
+            write!(f, "🂠 ")
         }
     }
 }
@@ -208,6 +212,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cards() {
+        assert_eq!(
+            &vec![
+                Card::new(Rank::Ace, Suit::Clubs),
+                Card::new(Rank::Two, Suit::Clubs),
+            ],
+            make_foundation(vec![
+                Card::new(Rank::Ace, Suit::Clubs),
+                Card::new(Rank::Two, Suit::Clubs),
+            ])
+            .cards(),
+        );
+    }
+
     fn make_foundation(cards: Vec<Card>) -> Foundation {
         Foundation(cards)
     }
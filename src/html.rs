@@ -0,0 +1,211 @@
+use super::{Action, Card, Game, Single, Tableau};
+
+/// The page that `replay` fills in. `{{DATA}}` is replaced with the deal and the moves as JSON;
+/// the script replays the moves itself, so the page works offline and stays small.
+const TEMPLATE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>RefCell replay</title>
+<style>
+body { margin: 0; padding: 1em; background: #1b5e20; color: #e8f5e9; font-family: sans-serif; }
+#board { display: grid; grid-template-columns: repeat(8, 64px); gap: 8px; }
+.label { text-align: center; }
+.pile { position: relative; width: 60px; height: 84px; border: 1px dashed #a5d6a7; border-radius: 5px; }
+.cascade { border-color: transparent; }
+.card { position: absolute; left: 0; width: 58px; height: 82px; border: 1px solid #424242; border-radius: 5px; background: #fff; color: #000; font-size: 15px; padding: 2px 4px; box-sizing: border-box; }
+.card.red { color: #c62828; }
+.card.moved { border: 3px solid #ffca28; }
+#controls { margin: 1em 0; }
+#moves { display: flex; flex-wrap: wrap; gap: 4px; padding: 0; list-style: none; }
+#moves li { padding: 2px 6px; border-radius: 3px; cursor: pointer; font-family: monospace; }
+#moves li.current { background: #ffca28; color: #000; }
+</style>
+</head>
+<body>
+<div id="board"></div>
+<div id="controls">
+<button id="first">&#x23EE;</button>
+<button id="back">&#x25C0;</button>
+<span id="position"></span>
+<button id="forward">&#x25B6;</button>
+<button id="last">&#x23ED;</button>
+</div>
+<ol id="moves"></ol>
+<script>
+const data = {{DATA}};
+const rankNames = { T: "10" };
+const suitSymbols = { C: "♣", D: "♦", H: "♥", S: "♠" };
+
+const positions = [data.start];
+for (const move of data.moves) {
+  const next = JSON.parse(JSON.stringify(positions[positions.length - 1]));
+  next[move.to].push(...next[move.from].splice(-move.count));
+  positions.push(next);
+}
+
+let current = 0;
+
+function cardElement(code, top, moved) {
+  const card = document.createElement("div");
+  const suit = code[1];
+  card.className = "card" + ("DH".includes(suit) ? " red" : "") + (moved ? " moved" : "");
+  card.style.top = top + "px";
+  card.textContent = (rankNames[code[0]] || code[0]) + suitSymbols[suit];
+  return card;
+}
+
+function render() {
+  const board = document.getElementById("board");
+  const position = positions[current];
+  const move = data.moves[current - 1];
+  board.replaceChildren();
+
+  for (const row of [["a", "b", "c", "d", "w", "x", "y", "z"], ["1", "2", "3", "4", "5", "6", "7", "8"]]) {
+    for (const pile of row) {
+      const label = document.createElement("div");
+      label.className = "label";
+      label.textContent = pile.toUpperCase();
+      board.appendChild(label);
+    }
+    for (const pile of row) {
+      const element = document.createElement("div");
+      const cards = position[pile];
+      const cascade = /[1-8]/.test(pile);
+      element.className = "pile" + (cascade ? " cascade" : "");
+      if (cascade) {
+        element.style.height = (84 + Math.max(cards.length - 1, 0) * 24) + "px";
+      }
+      const shown = cascade ? cards : cards.slice(-1);
+      shown.forEach((code, i) => {
+        const moved = move && move.to === pile && i >= shown.length - move.count;
+        element.appendChild(cardElement(code, cascade ? i * 24 : 0, moved));
+      });
+      board.appendChild(element);
+    }
+  }
+
+  document.getElementById("position").textContent = "Move " + current + " of " + data.moves.length;
+  document.querySelectorAll("#moves li").forEach((item, i) => {
+    item.className = i === current - 1 ? "current" : "";
+  });
+}
+
+function go(index) {
+  current = Math.max(0, Math.min(index, data.moves.length));
+  render();
+}
+
+data.moves.forEach((move, i) => {
+  const item = document.createElement("li");
+  item.textContent = move.label;
+  item.addEventListener("click", () => go(i + 1));
+  document.getElementById("moves").appendChild(item);
+});
+
+document.getElementById("first").addEventListener("click", () => go(0));
+document.getElementById("back").addEventListener("click", () => go(current - 1));
+document.getElementById("forward").addEventListener("click", () => go(current + 1));
+document.getElementById("last").addEventListener("click", () => go(data.moves.length));
+document.addEventListener("keydown", (event) => {
+  const keys = { ArrowLeft: current - 1, ArrowRight: current + 1, Home: 0, End: data.moves.length };
+  if (event.key in keys) {
+    go(keys[event.key]);
+  }
+});
+
+render();
+</script>
+</body>
+</html>
+"##;
+
+/// Write out a game as a standalone HTML page that steps through its moves from the deal.
+pub fn replay(game: &Game) -> String {
+    let moves: Vec<String> = game
+        .moves()
+        .iter()
+        .filter_map(|action| match action {
+            Action::MoveCard { from, to, count } => Some(format!(
+                r#"{{"label":"{}","from":"{}","to":"{}","count":{}}}"#,
+                action,
+                from,
+                to,
+                count.map_or(1, |count| count.get()),
+            )),
+            Action::Undo => None,
+        })
+        .collect();
+
+    let data = format!(
+        r#"{{"start":{},"moves":[{}]}}"#,
+        piles_json(&game.history()[0]),
+        moves.join(","),
+    );
+
+    TEMPLATE.replace("{{DATA}}", &data)
+}
+
+/// Every pile in the tableau, keyed by the character used to refer to it, with its cards listed
+/// from the bottom up.
+fn piles_json(tableau: &Tableau) -> String {
+    let cells = tableau
+        .cells
+        .iter()
+        .zip('a'..='d')
+        .map(|(cell, key)| (key, cards_json(cell.peek())));
+    let foundations = tableau
+        .foundations
+        .iter()
+        .zip('w'..='z')
+        .map(|(foundation, key)| (key, cards_json(foundation.cards())));
+    let cascades = tableau
+        .cascades
+        .iter()
+        .zip('1'..='8')
+        .map(|(cascade, key)| (key, cards_json(cascade.cards())));
+
+    let piles: Vec<String> = cells
+        .chain(foundations)
+        .chain(cascades)
+        .map(|(key, cards)| format!(r#""{}":[{}]"#, key, cards))
+        .collect();
+
+    format!("{{{}}}", piles.join(","))
+}
+
+fn cards_json<'a>(cards: impl IntoIterator<Item = &'a Card>) -> String {
+    cards
+        .into_iter()
+        .map(|card| {
+            format!(
+                r#""{}{}""#,
+                card.get_rank().as_char(),
+                card.get_suit().as_letter()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Deck;
+
+    #[test]
+    fn replay() {
+        let mut game = Game::deal(Deck::numbered(1));
+        game.action("3a".parse().unwrap()).unwrap();
+
+        let html = super::replay(&game);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("{{DATA}}"));
+        assert!(html.contains(r#""moves":[{"label":"3a","from":"3","to":"a","count":1}]"#));
+
+        // The starting position, not the current one
+        assert!(html.contains(r#""a":[],"#));
+        assert!(html.contains(r#""1":["JD","#));
+    }
+}
@@ -1,24 +1,110 @@
+// The code and tests here are written with `assert_eq!(false, …)`, `map_or` and `while let`.
+#![allow(
+    clippy::bool_assert_comparison,
+    clippy::unnecessary_map_or,
+    clippy::while_let_on_iterator
+)]
+
 use std::convert::TryInto;
 use std::fmt;
 use std::iter;
+use std::num::NonZeroU8;
+use std::time::{Duration, Instant};
 
 pub use action::{Action, FromCoordinate, ToCoordinate};
+pub use autosave::Autosave;
 pub use cascade::Cascade;
 pub use cell::Cell;
-pub use deck::{Card, Deck, Rank, Suit};
+pub use clock::{format_time, Split};
+pub use colour::ColourSupport;
+pub use deck::{Card, CardStyle, Deck, Palette, Rank, Suit, SuitColour};
+pub use dirs::{config_dir, data_dir};
 pub use foundation::Foundation;
+pub use locale::Language;
+pub use render::{AnsiRenderer, AsciiRenderer, DescriptionRenderer, Renderer, SvgRenderer, View};
+pub use scoring::Scoring;
 pub use single::Single;
+pub use stats::{Outcome, Stats};
+pub use undo::UndoMode;
+pub use variant::Variant;
 
 mod action;
+mod autosave;
 mod cascade;
 mod cell;
+mod clock;
+mod colour;
 mod deck;
+mod dirs;
 mod foundation;
+mod html;
+mod locale;
+mod render;
+mod save;
+mod scoring;
 mod single;
+mod stats;
+mod undo;
+mod variant;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Game {
     history: Vec<Tableau>,
+    number: Option<u32>,
+    undo_mode: UndoMode,
+    undos: usize,
+
+    /// When the first move was played, and how long after it each position after the deal was
+    /// reached.
+    started: Option<Instant>,
+    times: Vec<Duration>,
+}
+
+/// Which cards a frontend should draw attention to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Highlight {
+    /// No highlighting.
+    Off,
+
+    /// Cards that can be played directly to a foundation.
+    #[default]
+    Foundation,
+
+    /// Cards that can be played to a foundation or onto another cascade.
+    Movable,
+}
+
+impl Highlight {
+    /// The next option in the sequence, for frontends that cycle through them with a single key.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Foundation,
+            Self::Foundation => Self::Movable,
+            Self::Movable => Self::Off,
+        }
+    }
+}
+
+/// How a tableau should be drawn, for frontends that let the player choose.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DisplayOptions {
+    pub highlight: Highlight,
+    pub palette: Palette,
+    pub colours: ColourSupport,
+    pub cards: CardStyle,
+    pub language: Language,
+}
+
+impl DisplayOptions {
+    /// The default options, with colours to suit standard output and the language set in the
+    /// environment.
+    pub fn detect() -> Self {
+        Self {
+            colours: ColourSupport::detect(),
+            language: Language::detect(),
+            ..Self::default()
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -26,17 +112,31 @@ pub struct Tableau {
     pub cells: [Cell; 4],
     pub foundations: [Foundation; 4],
     pub cascades: [Cascade; 8],
+    pub variant: Variant,
 }
 
 impl Game {
     pub fn new(tableau: Tableau) -> Self {
         Self {
             history: vec![tableau],
+            number: None,
+            undo_mode: UndoMode::default(),
+            undos: 0,
+            started: None,
+            times: Vec::new(),
         }
     }
 
-    pub fn deal(mut deck: Deck) -> Self {
-        let mut tableau = Tableau::empty();
+    pub fn deal(deck: Deck) -> Self {
+        Self::deal_variant(deck, Variant::default())
+    }
+
+    /// Deal a game of any variant. They all share the same layout.
+    pub fn deal_variant(mut deck: Deck, variant: Variant) -> Self {
+        let mut tableau = Tableau {
+            variant,
+            ..Tableau::empty()
+        };
 
         iter::from_fn(|| deck.pop())
             .zip((0..8).cycle())
@@ -45,11 +145,40 @@ impl Game {
         Self::new(tableau)
     }
 
+    /// Deal one of the numbered games from Microsoft FreeCell, remembering its number.
+    pub fn deal_numbered(number: u32, variant: Variant) -> Self {
+        Self {
+            number: Some(number),
+            ..Self::deal_variant(Deck::numbered(number), variant)
+        }
+    }
+
+    /// The number of the deal, if the game was dealt with `deal_numbered`.
+    pub fn number(&self) -> Option<u32> {
+        self.number
+    }
+
+    /// The same game with a different undo mode, which should be chosen before any moves are
+    /// played.
+    pub fn with_undo_mode(self, undo_mode: UndoMode) -> Self {
+        Self { undo_mode, ..self }
+    }
+
+    pub fn undo_mode(&self) -> UndoMode {
+        self.undo_mode
+    }
+
     pub fn action(&mut self, action: Action) -> Result<(), &'static str> {
         match action {
             Action::Undo => {
-                if self.history.len() > 1 {
+                if self.undo_mode == UndoMode::Off {
+                    Err("Undo is turned off for this game.")
+                } else if self.undo_mode.remaining(self.undos) == Some(0) {
+                    Err("There are no undos left for this game.")
+                } else if self.history.len() > 1 {
                     self.history.pop();
+                    self.times.pop();
+                    self.undos += 1;
                     Ok(())
                 } else {
                     Err("You are already at the first move.")
@@ -57,6 +186,7 @@ impl Game {
             }
             Action::MoveCard { from, to, count } => {
                 let mut tableau = self.tableau().clone();
+                let variant = tableau.variant;
 
                 if let (FromCoordinate::Cascade(n_from), ToCoordinate::Cascade(n_to)) = (from, to) {
                     let (n_from, n_to) = (n_from as usize, n_to as usize);
@@ -67,25 +197,10 @@ impl Game {
                         return Err("That space is empty.");
                     }
 
-                    let max_stack_size = {
-                        let num_empty_cascades = tableau
-                            .cascades
-                            .iter()
-                            .enumerate()
-                            .filter(|(i, c)| ![n_from, n_to].contains(i) && c.is_empty())
-                            .count();
-
-                        let num_empty_cells =
-                            tableau.cells.iter().filter(|cell| cell.is_empty()).count();
-
-                        ((num_empty_cells + 1) * (num_empty_cascades + 1))
-                            .min(from_cascade.len())
-                            .min(
-                                count
-                                    .and_then(|i| u8::from(i).try_into().ok())
-                                    .unwrap_or(usize::MAX),
-                            )
-                    };
+                    let max_stack_size = tableau
+                        .max_stack_size(to_cascade.is_empty())
+                        .min(from_cascade.len())
+                        .min(count.map_or(usize::MAX, |i| u8::from(i).into()));
 
                     if let Some(expected_rank) = to_cascade
                         .cards()
@@ -96,10 +211,12 @@ impl Game {
                             if from_cascade.cards()[from_cascade.len() - i].get_rank()
                                 == expected_rank
                             {
-                                if let Some(stack) = tableau.cascades[n_from].try_pop_stack(i) {
-                                    match tableau.cascades[n_to].try_push_stack(stack) {
+                                if let Some(stack) =
+                                    tableau.cascades[n_from].try_pop_stack_in(i, variant)
+                                {
+                                    match tableau.cascades[n_to].try_push_stack_in(stack, variant) {
                                         Ok(()) => {
-                                            self.history.push(tableau);
+                                            self.push(tableau);
                                             return Ok(());
                                         }
                                         Err((stack, message)) => {
@@ -114,11 +231,11 @@ impl Game {
                         }
                     } else if let Some(count) = count {
                         if let Some(stack) = tableau.cascades[n_from]
-                            .try_pop_stack(max_stack_size.min(u8::from(count).try_into().unwrap()))
+                            .try_pop_stack_in(max_stack_size.min(u8::from(count).into()), variant)
                         {
-                            match tableau.cascades[n_to].try_push_stack(stack) {
+                            match tableau.cascades[n_to].try_push_stack_in(stack, variant) {
                                 Ok(()) => {
-                                    self.history.push(tableau);
+                                    self.push(tableau);
                                     return Ok(());
                                 }
                                 Err((stack, message)) => {
@@ -137,7 +254,9 @@ impl Game {
                 .ok_or("That space is empty.")?;
 
                 if let Err((card, message)) = match to {
-                    ToCoordinate::Cascade(n) => tableau.cascades[n as usize].try_push(card),
+                    ToCoordinate::Cascade(n) => {
+                        tableau.cascades[n as usize].try_push_in(card, variant)
+                    }
                     ToCoordinate::Cell(n) => tableau.cells[n as usize].try_push(card),
                     ToCoordinate::Foundation(n) => tableau.foundations[n as usize].try_push(card),
                 } {
@@ -150,23 +269,221 @@ impl Game {
 
                     Err(message)
                 } else {
-                    self.history.push(tableau);
+                    self.push(tableau);
                     Ok(())
                 }
             }
         }
     }
 
+    /// Add a position reached by a move, starting the clock if it's the first.
+    fn push(&mut self, tableau: Tableau) {
+        let time = match self.started {
+            Some(started) => started.elapsed(),
+            None => {
+                self.started = Some(Instant::now());
+                Duration::ZERO
+            }
+        };
+        self.history.push(tableau);
+        self.times.push(time);
+    }
+
+    /// Go back to the deal, discarding every move. This starts a fresh attempt, so the undos are
+    /// forgotten and the clock is reset too.
+    pub fn restart(&mut self) {
+        self.history.truncate(1);
+        self.undos = 0;
+        self.started = None;
+        self.times.clear();
+    }
+
     pub fn is_won(&self) -> bool {
-        self.tableau()
-            .cascades
-            .iter()
-            .all(|cascade| cascade.is_sequential())
+        self.tableau().is_won()
+    }
+
+    /// How long the game has taken, from the first move. The clock keeps running through undos,
+    /// and stops at the move that won the game.
+    pub fn elapsed(&self) -> Duration {
+        match self.history.iter().position(Tableau::is_won) {
+            Some(0) => Duration::ZERO,
+            Some(i) => self.times[i - 1],
+            None => self
+                .started
+                .map_or(Duration::ZERO, |started| started.elapsed()),
+        }
+    }
+
+    /// How long after the first move each of the moves that got to the current position was
+    /// played.
+    pub fn times(&self) -> &[Duration] {
+        &self.times
+    }
+
+    /// How long each of the moves that got to the current position took, from the one before it.
+    /// The first move starts the clock, so it always takes no time.
+    pub fn move_times(&self) -> Vec<Duration> {
+        iter::once(Duration::ZERO)
+            .chain(self.times.iter().copied())
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|pair| pair[1].saturating_sub(pair[0]))
+            .collect()
     }
 
-    fn tableau(&self) -> &Tableau {
+    /// The moments worth timing that the game has reached, in order, with how long after the first
+    /// move each was reached.
+    pub fn splits(&self) -> Vec<(Split, Duration)> {
+        clock::splits(self)
+    }
+
+    /// The time taken, how long the last move took, and the splits, as lines of text in the
+    /// player's language.
+    pub fn describe_times(&self, language: Language) -> Vec<String> {
+        clock::describe(self, language)
+    }
+
+    pub fn tableau(&self) -> &Tableau {
         self.history.last().unwrap()
     }
+
+    /// Every position the game has passed through, starting with the deal.
+    pub fn history(&self) -> &[Tableau] {
+        &self.history
+    }
+
+    /// How many moves have been taken back.
+    pub fn undos(&self) -> usize {
+        self.undos
+    }
+
+    /// How many more moves can be taken back, or `None` if there's no limit.
+    pub fn undos_left(&self) -> Option<usize> {
+        self.undo_mode.remaining(self.undos)
+    }
+
+    /// The moves that were played to get from the deal to the current position.
+    pub fn moves(&self) -> Vec<Action> {
+        self.history
+            .windows(2)
+            .filter_map(|pair| pair[0].move_to(&pair[1]))
+            .collect()
+    }
+
+    /// Every move that can be played from the current position, in the notation it would be
+    /// typed in. Moves that only shift cards between empty spaces are left out, a card is only
+    /// offered the first empty free cell or foundation, and a stack going to an empty cascade is
+    /// listed once for each size that would fit.
+    pub fn legal_moves(&self) -> Vec<Action> {
+        let tableau = self.tableau();
+        let mut moves = Vec::new();
+
+        let froms = (0..8)
+            .map(FromCoordinate::Cascade)
+            .chain((0..4).map(FromCoordinate::Cell));
+        for from in froms {
+            let tos = (0..4)
+                .map(ToCoordinate::Foundation)
+                .chain((0..8).map(ToCoordinate::Cascade))
+                .chain((0..4).map(ToCoordinate::Cell))
+                .filter(|&to| to != ToCoordinate::from(from));
+
+            for to in tos {
+                let len = tableau.pile_len(from.into());
+                let is_pointless = tableau.pile_len(to) == 0
+                    && match (from, to) {
+                        (FromCoordinate::Cell(_), ToCoordinate::Cell(_)) => true,
+                        (FromCoordinate::Cascade(_), ToCoordinate::Cascade(_)) => len == 1,
+                        (_, ToCoordinate::Foundation(n)) => {
+                            (0..n).any(|m| tableau.pile_len(ToCoordinate::Foundation(m)) == 0)
+                        }
+                        (_, ToCoordinate::Cell(n)) => {
+                            (0..n).any(|m| tableau.pile_len(ToCoordinate::Cell(m)) == 0)
+                        }
+                        _ => false,
+                    };
+                if len == 0 || is_pointless {
+                    continue;
+                }
+
+                // A count is only needed to move a stack to an empty cascade, and is otherwise
+                // worked out from the destination.
+                let counts = match to {
+                    ToCoordinate::Cascade(_) if tableau.pile_len(to) == 0 => 2..len,
+                    _ => 0..0,
+                };
+                let actions = iter::once(None)
+                    .chain(counts.map(|n| u8::try_from(n).ok().and_then(NonZeroU8::new)))
+                    .map(|count| Action::MoveCard { from, to, count });
+
+                for action in actions {
+                    let mut game = Game::new(tableau.clone());
+                    if game.action(action).is_ok() {
+                        if let Some(action) = tableau.move_to(game.tableau()) {
+                            if !moves.contains(&action) {
+                                moves.push(action);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        moves
+    }
+
+    /// A suggestion for what to play next. This doesn't look ahead, but prefers moves that make
+    /// progress: to the foundations first, then out of the free cells, then onto other cascades,
+    /// and only then into empty spaces. Stacks that are already in sequence aren't shuffled
+    /// between cascades.
+    pub fn hint(&self) -> Option<Action> {
+        let tableau = self.tableau();
+        let score = |action: &Action| match *action {
+            Action::MoveCard { from, to, count } => match (from, to) {
+                (_, ToCoordinate::Foundation(_)) => 5,
+                (FromCoordinate::Cell(_), ToCoordinate::Cascade(_)) => 4,
+                (FromCoordinate::Cascade(n), ToCoordinate::Cascade(_)) => {
+                    let cards = tableau.cascades[n as usize].cards();
+                    let moved = count.map_or(1, |count| usize::from(count.get()));
+                    let is_in_sequence = cards.len() > moved
+                        && tableau
+                            .variant
+                            .is_legal(&cards[cards.len() - moved - 1], &cards[cards.len() - moved]);
+
+                    match (is_in_sequence, tableau.pile_len(to)) {
+                        (true, _) => -1,
+                        (false, 0) => 1,
+                        (false, _) => 3,
+                    }
+                }
+                (_, ToCoordinate::Cell(_)) => 0,
+            },
+            Action::Undo => -1,
+        };
+
+        tableau.safe_foundation_move().or_else(|| {
+            self.legal_moves()
+                .into_iter()
+                .rev()
+                .max_by_key(score)
+                .filter(|action| score(action) >= 0)
+        })
+    }
+
+    /// A standalone HTML page that replays the game from the deal, one move at a time.
+    pub fn to_html(&self) -> String {
+        html::replay(self)
+    }
+
+    /// The game as plain text that `load` can read back: the deal and the moves played so far.
+    pub fn save(&self) -> String {
+        save::save(self)
+    }
+
+    /// Read back a game written by `save`.
+    pub fn load(input: &str) -> Result<Self, &'static str> {
+        save::load(input)
+    }
 }
 
 impl Tableau {
@@ -187,133 +504,235 @@ impl Tableau {
                 .collect::<Vec<Cascade>>()
                 .try_into()
                 .unwrap(),
+            variant: Variant::default(),
         }
     }
-}
 
-impl fmt::Display for Game {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(f, "{}", self.tableau())
+    /// Whether every cascade is in sequence, so that the remaining cards can all be played to the
+    /// foundations.
+    pub fn is_won(&self) -> bool {
+        self.cascades.iter().all(|cascade| cascade.is_sequential())
     }
-}
 
-impl fmt::Display for Tableau {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // cells and foundations
-        {
-            writeln!(f, " A   B   C   D   W   X   Y   Z")?;
-            let top_row: Vec<String> = self
-                .cells
+    /// The largest stack that can be moved from one cascade to another, using the free cells and
+    /// empty cascades as temporary space.
+    pub fn max_stack_size(&self, to_empty_cascade: bool) -> usize {
+        let num_empty_cells = self.cells.iter().filter(|cell| cell.is_empty()).count();
+        let num_empty_cascades = self
+            .cascades
+            .iter()
+            .filter(|cascade| cascade.is_empty())
+            .count()
+            .saturating_sub(usize::from(to_empty_cascade));
+
+        (num_empty_cells + 1) * (num_empty_cascades + 1)
+    }
+
+    /// Whether `card` is the next one needed on a foundation.
+    pub fn is_next_for_foundation(&self, card: &Card) -> bool {
+        self.foundations
+            .iter()
+            .any(|foundation| foundation.is_legal(card))
+    }
+
+    /// Whether the card at `depth` in a pile can be played somewhere useful, either to a foundation
+    /// or onto another cascade, taking any cards stacked on top of it along with it. Free cells and
+    /// empty cascades are not counted, since any exposed card can go there.
+    pub fn has_destination(&self, from: FromCoordinate, depth: usize) -> bool {
+        let stack = match from {
+            FromCoordinate::Cascade(n) => self.cascades[n as usize].cards().get(depth..),
+            FromCoordinate::Cell(n) => self.cells[n as usize].peek().map(std::slice::from_ref),
+        };
+
+        let stack = match stack {
+            Some(stack) if !stack.is_empty() => stack,
+            _ => return false,
+        };
+
+        if stack.len() == 1 && self.is_next_for_foundation(&stack[0]) {
+            return true;
+        }
+
+        stack.len() <= self.max_stack_size(false)
+            && stack
+                .windows(2)
+                .all(|pair| self.variant.is_legal(&pair[0], &pair[1]))
+            && self
+                .cascades
                 .iter()
-                .map(|cell| cell.peek())
-                .chain(self.foundations.iter().map(|foundation| foundation.peek()))
-                .map(|card| match card {
-                    Some(card) => card.to_string(),
-                    None => "\x1b[2;7m   \x1b[0m\n\x1b[2;7m   \x1b[0m".to_string(),
+                .enumerate()
+                .filter(|(i, cascade)| {
+                    !cascade.is_empty() && from != ToCoordinate::Cascade(*i as u8)
                 })
-                .collect();
+                .any(|(_, cascade)| cascade.stack_is_legal_in(stack, self.variant))
+    }
 
-            top_row
-                .iter()
-                .try_for_each(|s| write!(f, "{} ", s.lines().next().unwrap()))?;
-            writeln!(f)?;
+    /// Whether the card at `depth` in a pile should be highlighted.
+    pub fn is_highlighted(&self, from: FromCoordinate, depth: usize, highlight: Highlight) -> bool {
+        let card = match from {
+            FromCoordinate::Cascade(n) => self.cascades[n as usize].cards().get(depth),
+            FromCoordinate::Cell(n) => self.cells[n as usize].peek(),
+        };
 
-            top_row
-                .iter()
-                .try_for_each(|s| write!(f, "{} ", s.lines().last().unwrap()))?;
-            writeln!(f)?;
+        match (highlight, card) {
+            (_, None) | (Highlight::Off, _) => false,
+            (Highlight::Foundation, Some(card)) => self.is_next_for_foundation(card),
+            (Highlight::Movable, Some(card)) => {
+                self.is_next_for_foundation(card) || self.has_destination(from, depth)
+            }
         }
+    }
 
-        writeln!(f)?;
+    /// Display the tableau with a given set of options, rather than the defaults.
+    pub fn display(&self, options: DisplayOptions) -> TableauDisplay<'_> {
+        TableauDisplay(self, options)
+    }
 
-        // cascades
-        {
-            writeln!(f, " 1   2   3   4   5   6   7   8")?;
+    /// Draw the tableau as a standalone SVG image with the default options.
+    pub fn to_svg(&self) -> String {
+        SvgRenderer.render(self, &View::default())
+    }
 
-            let longest_cascade = self
-                .cascades
-                .iter()
-                .map(|cascade| cascade.len())
-                .max()
-                .unwrap_or(0);
+    /// The move that turns this tableau into `next`, if they're one move apart. The count is only
+    /// given when more than one card moved.
+    pub fn move_to(&self, next: &Tableau) -> Option<Action> {
+        let from = (0..8)
+            .map(FromCoordinate::Cascade)
+            .chain((0..4).map(FromCoordinate::Cell))
+            .find(|&from| self.pile_len(from.into()) > next.pile_len(from.into()))?;
+        let to = (0..8)
+            .map(ToCoordinate::Cascade)
+            .chain((0..4).map(ToCoordinate::Cell))
+            .chain((0..4).map(ToCoordinate::Foundation))
+            .find(|&to| self.pile_len(to) < next.pile_len(to))?;
+        let count = self.pile_len(from.into()) - next.pile_len(from.into());
+
+        Some(Action::MoveCard {
+            from,
+            to,
+            count: Some(count)
+                .filter(|&count| count > 1)
+                .and_then(|count| u8::try_from(count).ok())
+                .and_then(NonZeroU8::new),
+        })
+    }
 
-            for row in 0..longest_cascade + 1 {
-                self.cascades.iter().try_for_each(|cascade| {
-                    cascade
-                        .cards()
-                        .get(row)
-                        .map(|card| write!(f, "{} ", card.to_string().lines().next().unwrap()))
-                        .or_else(|| {
-                            row.checked_sub(1)
-                                .and_then(|prev_row| cascade.cards().get(prev_row))
-                                .map(|card| {
-                                    write!(f, "{} ", card.to_string().lines().last().unwrap())
-                                })
-                        })
-                        .unwrap_or_else(|| write!(f, "    "))
-                })?;
-
-                writeln!(f)?;
+    fn pile_len(&self, pile: ToCoordinate) -> usize {
+        match pile {
+            ToCoordinate::Cascade(n) => self.cascades[n as usize].len(),
+            ToCoordinate::Cell(n) => self.cells[n as usize].peek().map_or(0, |_| 1),
+            ToCoordinate::Foundation(n) => self.foundations[n as usize].cards().len(),
+        }
+    }
+
+    /// Find the lowest-ranked exposed card that can be moved to a foundation, if any. Used to
+    /// finish off a game once the outcome is no longer in doubt.
+    pub fn foundation_move(&self) -> Option<Action> {
+        self.foundation_moves()
+            .min_by_key(|(card, _)| card.get_rank())
+            .map(|(_, action)| action)
+    }
+
+    /// Find a card that can be moved to a foundation without any risk of it being needed to
+    /// build on, for frontends that play those moves automatically. That's any card once every
+    /// card that could go on it is already on a foundation, and aces and twos at any time.
+    pub fn safe_foundation_move(&self) -> Option<Action> {
+        self.foundation_moves()
+            .filter(|(card, _)| self.is_safe_for_foundation(card))
+            .min_by_key(|(card, _)| card.get_rank())
+            .map(|(_, action)| action)
+    }
+
+    fn is_safe_for_foundation(&self, card: &Card) -> bool {
+        let rank = u8::from(card.get_rank());
+
+        match self.variant {
+            // Only the same suit builds on a card, and the card below it is already up.
+            Variant::BakersGame => true,
+            Variant::FreeCell => {
+                let lowest_opposite = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades]
+                    .into_iter()
+                    .filter(|suit| suit.is_red() != card.get_suit().is_red())
+                    .map(|suit| {
+                        self.foundations
+                            .iter()
+                            .find(|foundation| foundation.get_suit() == Some(suit))
+                            .and_then(Foundation::get_rank)
+                            .map_or(0, u8::from)
+                    })
+                    .min()
+                    .unwrap_or(0);
+
+                rank <= 2 || rank <= lowest_opposite + 1
             }
         }
+    }
 
-        Ok(())
+    /// Every exposed card that can be moved to a foundation, along with the move.
+    fn foundation_moves(&self) -> impl Iterator<Item = (&Card, Action)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, cell)| {
+                cell.peek()
+                    .map(|card| (FromCoordinate::Cell(i as u8), card))
+            })
+            .chain(self.cascades.iter().enumerate().filter_map(|(i, cascade)| {
+                cascade
+                    .cards()
+                    .last()
+                    .map(|card| (FromCoordinate::Cascade(i as u8), card))
+            }))
+            .filter_map(|(from, card)| {
+                self.foundations
+                    .iter()
+                    .position(|foundation| foundation.is_legal(card))
+                    .map(|n| {
+                        (
+                            card,
+                            Action::MoveCard {
+                                from,
+                                to: ToCoordinate::Foundation(n as u8),
+                                count: None,
+                            },
+                        )
+                    })
+            })
     }
 }
 
-/*
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.tableau())
+    }
+}
+
+/// A tableau together with the options it should be displayed with.
+pub struct TableauDisplay<'a>(&'a Tableau, DisplayOptions);
+
+/// The tableau in plain text, with the default options. Use `Tableau::display` to draw it in
+/// colour or in another language.
 impl fmt::Display for Tableau {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "ａｂｃｄ  ｗｘｙｚ")?;
-        self.cells
-            .iter()
-            .try_for_each(|cell| write!(f, "{}", cell))?;
-        write!(f, "  ")?;
-        self.foundations
-            .iter()
-            .try_for_each(|cell| write!(f, "{}", cell))?;
-        write!(f, "\n\n")?;
-        writeln!(f, " １２３４５６７８")?;
-
-        let foundation_suits: HashMap<Suit, u8> = self
-            .foundations
-            .iter()
-            .filter_map(|f| f.get_suit().map(|suit| (suit, f.get_rank())))
-            .collect();
+        let options = DisplayOptions {
+            colours: ColourSupport::Plain,
+            ..DisplayOptions::default()
+        };
+        write!(f, "{}", self.display(options))
+    }
+}
 
-        let longest_cascade = self
-            .cascades
-            .iter()
-            .map(|cascade| cascade.len())
-            .max()
-            .unwrap_or(0);
-
-        for row in 0..longest_cascade {
-            write!(f, " ")?;
-            for cascade in self.cascades.iter() {
-                if let Some(card) = cascade.cards().get(row) {
-                    if foundation_suits
-                        .get(&card.get_suit())
-                        .cloned()
-                        .unwrap_or_default()
-                        + 1
-                        == card.get_rank()
-                    {
-                        write!(f, "\x1b[7m{}", card)?;
-                    } else {
-                        write!(f, "{}", card)?;
-                    }
-                } else {
-                    write!(f, "  ")?;
-                }
-            }
-            write!(f, "\n")?;
-        }
+impl fmt::Display for TableauDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let TableauDisplay(tableau, options) = *self;
+        let view = View {
+            options,
+            ..View::default()
+        };
 
-        Ok(())
+        f.write_str(&AnsiRenderer.render(tableau, &view))
     }
 }
-*/
 
 #[cfg(test)]
 mod tests {
@@ -567,6 +986,360 @@ mod tests {
         );
     }
 
+    #[test]
+    fn history() {
+        let mut game = {
+            let mut tableau = Tableau::empty();
+            tableau.cascades[0].push(Card::new(Rank::Ace, Suit::Clubs));
+            Game::new(tableau)
+        };
+
+        game.action(Action::MoveCard {
+            from: FromCoordinate::Cascade(0),
+            to: ToCoordinate::Cell(0),
+            count: None,
+        })
+        .unwrap();
+
+        assert_eq!(2, game.history().len());
+        assert_eq!(1, game.history()[0].cascades[0].len());
+        assert!(game.history()[1].cascades[0].is_empty());
+
+        game.action(Action::Undo).unwrap();
+        assert_eq!(1, game.history().len());
+    }
+
+    #[test]
+    fn undo_modes() {
+        let mut game = Game::deal(Deck::numbered(1)).with_undo_mode(UndoMode::Limited(1));
+        game.action("3a".parse().unwrap()).unwrap();
+        game.action("3b".parse().unwrap()).unwrap();
+
+        assert_eq!(Some(1), game.undos_left());
+        game.action(Action::Undo).unwrap();
+        assert_eq!(Some(0), game.undos_left());
+        assert_eq!(
+            Err("There are no undos left for this game."),
+            game.action(Action::Undo)
+        );
+        assert_eq!(2, game.history().len());
+
+        // Restarting is a fresh attempt, with every undo back.
+        game.restart();
+        assert_eq!(Some(1), game.undos_left());
+
+        let mut game = Game::deal(Deck::numbered(1)).with_undo_mode(UndoMode::Off);
+        game.action("3a".parse().unwrap()).unwrap();
+        assert_eq!(
+            Err("Undo is turned off for this game."),
+            game.action(Action::Undo)
+        );
+        assert_eq!(UndoMode::Off, game.undo_mode());
+    }
+
+    #[test]
+    fn display_is_plain() {
+        // Whatever the terminal, `to_string` gives the same text, with colour left to `display`.
+        let game = Game::deal(Deck::numbered(1));
+        assert!(!game.to_string().contains('\x1b'));
+        assert!(!Tableau::empty().to_string().contains('\x1b'));
+
+        let options = DisplayOptions {
+            colours: ColourSupport::Ansi16,
+            ..DisplayOptions::default()
+        };
+        assert!(game.tableau().display(options).to_string().contains('\x1b'));
+    }
+
+    #[test]
+    fn restart() {
+        let mut game = Game::deal(Deck::numbered(1));
+        let deal = game.tableau().clone();
+
+        game.action(Action::MoveCard {
+            from: FromCoordinate::Cascade(0),
+            to: ToCoordinate::Cell(0),
+            count: None,
+        })
+        .unwrap();
+        game.action(Action::MoveCard {
+            from: FromCoordinate::Cascade(1),
+            to: ToCoordinate::Cell(1),
+            count: None,
+        })
+        .unwrap();
+
+        game.restart();
+        assert_eq!(1, game.history().len());
+        assert_eq!(deal.cascades, game.tableau().cascades);
+        assert_eq!(deal.cells, game.tableau().cells);
+
+        game.restart();
+        assert_eq!(1, game.history().len());
+    }
+
+    #[test]
+    fn max_stack_size() {
+        let mut tableau = Tableau::empty();
+        assert_eq!(45, tableau.max_stack_size(false));
+        assert_eq!(40, tableau.max_stack_size(true));
+
+        (0..8).for_each(|i| tableau.cascades[i].push(Card::new(Rank::King, Suit::Hearts)));
+        tableau.cells[0]
+            .try_push(Card::new(Rank::King, Suit::Clubs))
+            .unwrap();
+        assert_eq!(4, tableau.max_stack_size(false));
+        assert_eq!(4, tableau.max_stack_size(true));
+
+        tableau.cascades[3].pop();
+        assert_eq!(8, tableau.max_stack_size(false));
+        assert_eq!(4, tableau.max_stack_size(true));
+    }
+
+    #[test]
+    fn is_next_for_foundation() {
+        let mut tableau = Tableau::empty();
+        assert!(tableau.is_next_for_foundation(&Card::new(Rank::Ace, Suit::Spades)));
+        assert!(!tableau.is_next_for_foundation(&Card::new(Rank::Two, Suit::Spades)));
+
+        tableau.foundations[2]
+            .try_push(Card::new(Rank::Ace, Suit::Spades))
+            .unwrap();
+        assert!(tableau.is_next_for_foundation(&Card::new(Rank::Two, Suit::Spades)));
+        assert!(!tableau.is_next_for_foundation(&Card::new(Rank::Two, Suit::Hearts)));
+    }
+
+    #[test]
+    fn has_destination() {
+        let mut tableau = Tableau::empty();
+        tableau.cascades[0].push_stack(vec![
+            Card::new(Rank::Nine, Suit::Clubs),
+            Card::new(Rank::Eight, Suit::Hearts),
+            Card::new(Rank::Seven, Suit::Spades),
+        ]);
+        tableau.cascades[1].push(Card::new(Rank::Nine, Suit::Spades));
+        tableau.cells[0]
+            .try_push(Card::new(Rank::Ace, Suit::Hearts))
+            .unwrap();
+        tableau.cells[1]
+            .try_push(Card::new(Rank::Two, Suit::Hearts))
+            .unwrap();
+
+        // Onto a foundation
+        assert!(tableau.has_destination(FromCoordinate::Cell(0), 0));
+        assert!(!tableau.has_destination(FromCoordinate::Cell(1), 0));
+        assert!(!tableau.has_destination(FromCoordinate::Cell(2), 0));
+
+        // Onto another cascade, as part of a stack
+        assert!(!tableau.has_destination(FromCoordinate::Cascade(0), 0));
+        assert!(tableau.has_destination(FromCoordinate::Cascade(0), 1));
+        assert!(!tableau.has_destination(FromCoordinate::Cascade(0), 2));
+        assert!(!tableau.has_destination(FromCoordinate::Cascade(0), 3));
+
+        // Nothing to build on, and empty cascades don't count
+        assert!(!tableau.has_destination(FromCoordinate::Cascade(1), 0));
+    }
+
+    #[test]
+    fn is_highlighted() {
+        let mut tableau = Tableau::empty();
+        tableau.cascades[0].push_stack(vec![
+            Card::new(Rank::Ace, Suit::Clubs),
+            Card::new(Rank::Eight, Suit::Hearts),
+        ]);
+        tableau.cascades[1].push(Card::new(Rank::Nine, Suit::Spades));
+
+        let from = FromCoordinate::Cascade(0);
+
+        assert!(!tableau.is_highlighted(from, 0, Highlight::Off));
+        assert!(!tableau.is_highlighted(from, 1, Highlight::Off));
+
+        assert!(tableau.is_highlighted(from, 0, Highlight::Foundation));
+        assert!(!tableau.is_highlighted(from, 1, Highlight::Foundation));
+
+        assert!(tableau.is_highlighted(from, 0, Highlight::Movable));
+        assert!(tableau.is_highlighted(from, 1, Highlight::Movable));
+
+        assert!(!tableau.is_highlighted(from, 2, Highlight::Movable));
+        assert!(!tableau.is_highlighted(FromCoordinate::Cell(0), 0, Highlight::Movable));
+    }
+
+    #[test]
+    fn foundation_move() {
+        let mut tableau = Tableau::empty();
+        assert_eq!(None, tableau.foundation_move());
+
+        tableau.cascades[0].push(Card::new(Rank::Two, Suit::Hearts));
+        tableau.cascades[1].push(Card::new(Rank::Three, Suit::Clubs));
+        assert_eq!(None, tableau.foundation_move());
+
+        tableau.cells[2]
+            .try_push(Card::new(Rank::Ace, Suit::Hearts))
+            .unwrap();
+        tableau.foundations[0]
+            .try_push(Card::new(Rank::Ace, Suit::Clubs))
+            .unwrap();
+        tableau.foundations[0]
+            .try_push(Card::new(Rank::Two, Suit::Clubs))
+            .unwrap();
+
+        assert_eq!(
+            Some(Action::MoveCard {
+                from: FromCoordinate::Cell(2),
+                to: ToCoordinate::Foundation(1),
+                count: None,
+            }),
+            tableau.foundation_move(),
+        );
+
+        tableau.cells[2].take();
+        tableau.foundations[1]
+            .try_push(Card::new(Rank::Ace, Suit::Hearts))
+            .unwrap();
+
+        assert_eq!(
+            Some(Action::MoveCard {
+                from: FromCoordinate::Cascade(0),
+                to: ToCoordinate::Foundation(1),
+                count: None,
+            }),
+            tableau.foundation_move(),
+        );
+    }
+
+    #[test]
+    fn safe_foundation_move() {
+        let mut tableau = Tableau::empty();
+        for rank in [Rank::Ace, Rank::Two, Rank::Three] {
+            tableau.foundations[0]
+                .try_push(Card::new(rank, Suit::Clubs))
+                .unwrap();
+        }
+        for rank in [Rank::Ace, Rank::Two] {
+            tableau.foundations[1]
+                .try_push(Card::new(rank, Suit::Hearts))
+                .unwrap();
+        }
+        tableau.cascades[0].push(Card::new(Rank::Four, Suit::Clubs));
+        tableau.cascades[1].push(Card::new(Rank::Three, Suit::Hearts));
+
+        // The three of hearts might be needed for the two of spades.
+        assert!(tableau.foundation_move().is_some());
+        assert_eq!(None, tableau.safe_foundation_move());
+
+        let three_of_hearts = Some(Action::MoveCard {
+            from: FromCoordinate::Cascade(1),
+            to: ToCoordinate::Foundation(1),
+            count: None,
+        });
+
+        tableau.variant = Variant::BakersGame;
+        assert_eq!(three_of_hearts, tableau.safe_foundation_move());
+
+        tableau.variant = Variant::FreeCell;
+        for rank in [Rank::Ace, Rank::Two] {
+            tableau.foundations[2]
+                .try_push(Card::new(rank, Suit::Spades))
+                .unwrap();
+        }
+        assert_eq!(three_of_hearts, tableau.safe_foundation_move());
+    }
+
+    #[test]
+    fn legal_moves() {
+        let mut tableau = Tableau::empty();
+        tableau.cascades[0].push(Card::new(Rank::King, Suit::Spades));
+        tableau.cascades[0].push(Card::new(Rank::Queen, Suit::Hearts));
+        tableau.cascades[0].push(Card::new(Rank::Jack, Suit::Clubs));
+        tableau.cascades[1].push(Card::new(Rank::Queen, Suit::Diamonds));
+        tableau.cascades[2].push(Card::new(Rank::Ace, Suit::Spades));
+        tableau.cascades[3].push(Card::new(Rank::King, Suit::Clubs));
+        let game = Game::new(tableau);
+
+        let moves: Vec<String> = game.legal_moves().iter().map(Action::to_string).collect();
+
+        for expected in ["12", "2+14", "15", "2+15", "1a", "24", "3w", "4a"] {
+            assert!(moves.contains(&expected.to_string()), "{}", expected);
+        }
+        for unexpected in ["13", "3+15", "3x", "35", "1b"] {
+            assert!(!moves.contains(&unexpected.to_string()), "{}", unexpected);
+        }
+        assert_eq!(16, moves.len());
+    }
+
+    #[test]
+    fn hint() {
+        let mut tableau = Tableau::empty();
+        tableau.cascades[0].push(Card::new(Rank::King, Suit::Spades));
+        tableau.cascades[0].push(Card::new(Rank::Queen, Suit::Hearts));
+        tableau.cascades[0].push(Card::new(Rank::Jack, Suit::Clubs));
+        tableau.cascades[1].push(Card::new(Rank::Queen, Suit::Diamonds));
+        tableau.cascades[2].push(Card::new(Rank::Ace, Suit::Spades));
+        tableau.cascades[3].push(Card::new(Rank::King, Suit::Clubs));
+        let mut game = Game::new(tableau);
+
+        assert_eq!(Some("3w".parse().unwrap()), game.hint());
+        game.action("3w".parse().unwrap()).unwrap();
+
+        // The jack and queen are already in sequence, so the free queen is the one to move.
+        assert_eq!(Some("24".parse().unwrap()), game.hint());
+    }
+
+    #[test]
+    fn moves() {
+        let mut tableau = Tableau::empty();
+        tableau.cascades[0].push(Card::new(Rank::King, Suit::Spades));
+        tableau.cascades[0].push(Card::new(Rank::Queen, Suit::Hearts));
+        tableau.cascades[0].push(Card::new(Rank::Jack, Suit::Clubs));
+        tableau.cascades[1].push(Card::new(Rank::Queen, Suit::Diamonds));
+        tableau.cascades[2].push(Card::new(Rank::Ace, Suit::Spades));
+        tableau.cascades[3].push(Card::new(Rank::King, Suit::Clubs));
+
+        let mut game = Game::new(tableau.clone());
+        assert!(game.moves().is_empty());
+
+        for action in ["3w", "1a", "a2", "+24"] {
+            game.action(action.parse().unwrap()).unwrap();
+        }
+
+        let moves: Vec<String> = game.moves().iter().map(Action::to_string).collect();
+        assert_eq!(vec!["3w", "1a", "a2", "2+24"], moves);
+
+        // Replaying the recorded moves reaches the same position.
+        let mut replayed = Game::new(tableau);
+        game.moves()
+            .into_iter()
+            .for_each(|action| replayed.action(action).unwrap());
+        assert_eq!(game.tableau().to_string(), replayed.tableau().to_string(),);
+    }
+
+    #[test]
+    fn display_glyphs() {
+        let mut tableau = Tableau::empty();
+        tableau.cells[1]
+            .try_push(Card::new(Rank::King, Suit::Hearts))
+            .unwrap();
+        tableau.cascades[0].push(Card::new(Rank::Queen, Suit::Spades));
+        tableau.cascades[0].push(Card::new(Rank::Jack, Suit::Diamonds));
+        tableau.cascades[2].push(Card::new(Rank::Ace, Suit::Clubs));
+
+        let options = DisplayOptions {
+            colours: ColourSupport::Plain,
+            cards: CardStyle::Glyph,
+            ..DisplayOptions::default()
+        };
+
+        assert_eq!(
+            "A B C D W X Y Z\n\
+             🂠 🂾 🂠 🂠 🂠 🂠 🂠 🂠 \n\
+             \n\
+             1 2 3 4 5 6 7 8\n\
+             🂭   🃑           \n\
+             🃋               \n",
+            tableau.display(options).to_string(),
+        );
+    }
+
     #[test]
     fn action_illegal_empty() {
         let mut game = Game::new(Tableau::empty());
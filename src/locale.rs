@@ -0,0 +1,769 @@
+use super::{Action, Card, Rank, Suit, ToCoordinate};
+use std::env;
+use std::fmt::{self, Write};
+use std::str;
+
+/// A language that messages, card names and the letters typed for moves can be shown in.
+///
+/// Messages are looked up by their English text, as with gettext, so the library's errors are
+/// still plain `&'static str`s and anything missing from a catalog is shown in English. Message
+/// templates mark where values go with `{}`, filled in order by `format`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+
+/// Everything that changes from one language to another.
+struct Catalog {
+    /// Each English message alongside its translation.
+    messages: &'static [(&'static str, &'static str)],
+
+    /// The letters typed for the free cells and foundations, in place of a-d and w-z.
+    cells: [char; 4],
+    foundations: [char; 4],
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::French];
+
+    /// The language's ISO 639-1 code, as given to `--language` and found in locale names.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::French => "fr",
+        }
+    }
+
+    /// The language set for messages in the environment, the same way as for other programs:
+    /// `LC_ALL`, then `LC_MESSAGES`, then `LANG`. English if it isn't one that's supported.
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|locale| !locale.is_empty())
+            .and_then(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// The language of a locale name such as "fr_FR.UTF-8".
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        locale
+            .split(['_', '.', '@'])
+            .next()
+            .and_then(|code| code.parse().ok())
+    }
+
+    fn catalog(self) -> &'static Catalog {
+        match self {
+            Self::English => &ENGLISH,
+            Self::French => &FRENCH,
+        }
+    }
+
+    /// The message in this language.
+    pub fn tr(self, message: &'static str) -> &'static str {
+        self.catalog()
+            .messages
+            .iter()
+            .find(|(english, _)| *english == message)
+            .map_or(message, |(_, translation)| translation)
+    }
+
+    /// The message in this language, with each `{}` replaced by the next of `args`.
+    pub fn format(self, message: &'static str, args: &[&dyn fmt::Display]) -> String {
+        let mut output = String::new();
+        let mut args = args.iter();
+
+        for (i, part) in self.tr(message).split("{}").enumerate() {
+            if i > 0 {
+                if let Some(arg) = args.next() {
+                    write!(output, "{}", arg).unwrap();
+                }
+            }
+            output.push_str(part);
+        }
+
+        output
+    }
+
+    pub fn rank_name(self, rank: Rank) -> &'static str {
+        self.tr(rank.name())
+    }
+
+    pub fn suit_name(self, suit: Suit) -> &'static str {
+        self.tr(suit.name())
+    }
+
+    /// The card in words, such as "Queen of hearts" or "7 of spades" in English, for output
+    /// that's read aloud rather than looked at.
+    pub fn card_name(self, card: &Card) -> String {
+        self.format(
+            "{} of {}",
+            &[
+                &self.rank_name(card.get_rank()),
+                &self.suit_name(card.get_suit()),
+            ],
+        )
+    }
+
+    /// The letter a free cell or foundation is labelled and typed with. Cascades are numbered.
+    pub fn pile_letter(self, pile: ToCoordinate) -> Option<char> {
+        match pile {
+            ToCoordinate::Cascade(_) => None,
+            ToCoordinate::Cell(n) => Some(self.catalog().cells[n as usize]),
+            ToCoordinate::Foundation(n) => Some(self.catalog().foundations[n as usize]),
+        }
+    }
+
+    /// Read a move typed with this language's letters.
+    pub fn parse_action(self, input: &str) -> Result<Action, &'static str> {
+        let english = Self::English.catalog();
+        let catalog = self.catalog();
+        let input: String = input
+            .chars()
+            .map(|c| translate_letter(c, catalog, english))
+            .collect();
+
+        input.parse().map_err(|message| self.tr(message))
+    }
+
+    /// Write a move with this language's letters, so that `parse_action` reads it back.
+    pub fn action_string(self, action: &Action) -> String {
+        let english = Self::English.catalog();
+        let catalog = self.catalog();
+
+        action
+            .to_string()
+            .chars()
+            .map(|c| translate_letter(c, english, catalog))
+            .collect()
+    }
+
+    /// The name of a pile, such as "free cell A", for describing moves.
+    pub fn pile_name(self, pile: ToCoordinate) -> String {
+        let letter = self
+            .pile_letter(pile)
+            .map(|letter| letter.to_ascii_uppercase());
+
+        match pile {
+            ToCoordinate::Cascade(n) => self.format("cascade {}", &[&(n + 1)]),
+            ToCoordinate::Cell(_) => self.format("free cell {}", &[&letter.unwrap()]),
+            ToCoordinate::Foundation(_) => self.format("foundation {}", &[&letter.unwrap()]),
+        }
+    }
+}
+
+/// The letter in `to` that stands for the same pile as `c` does in `from`, or `c` itself if it
+/// isn't a pile letter in `from`.
+fn translate_letter(c: char, from: &Catalog, to: &Catalog) -> char {
+    let lower = c.to_ascii_lowercase();
+
+    from.cells
+        .iter()
+        .zip(to.cells)
+        .chain(from.foundations.iter().zip(to.foundations))
+        .find(|(letter, _)| **letter == lower)
+        .map_or(c, |(_, letter)| letter)
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl str::FromStr for Language {
+    type Err = &'static str;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.as_str().eq_ignore_ascii_case(input.trim()))
+            .ok_or("Unknown language.")
+    }
+}
+
+const ENGLISH: Catalog = Catalog {
+    messages: &[],
+    cells: ['a', 'b', 'c', 'd'],
+    foundations: ['w', 'x', 'y', 'z'],
+};
+
+/// The pile letters are only labels, so French keeps the English ones, which also keeps written
+/// move lists the same in both languages.
+const FRENCH: Catalog = Catalog {
+    messages: &[
+        // Cards
+        ("Ace", "As"),
+        ("Jack", "Valet"),
+        ("Queen", "Dame"),
+        ("King", "Roi"),
+        ("clubs", "trèfle"),
+        ("diamonds", "carreau"),
+        ("hearts", "cœur"),
+        ("spades", "pique"),
+        ("{} of {}", "{} de {}"),
+        // Errors from the library
+        ("Invalid input.", "Saisie non valide."),
+        ("Invalid count", "Nombre non valide"),
+        (
+            "The source and destination are the same.",
+            "La source et la destination sont identiques.",
+        ),
+        (
+            "You cannot take a card from a foundation.",
+            "Impossible de reprendre une carte d'une fondation.",
+        ),
+        (
+            "That card cannot go on that cascade.",
+            "Cette carte ne peut pas aller sur cette colonne.",
+        ),
+        (
+            "Those cards cannot go on that cascade.",
+            "Ces cartes ne peuvent pas aller sur cette colonne.",
+        ),
+        (
+            "A card is already present on that cell.",
+            "Cette cellule contient déjà une carte.",
+        ),
+        (
+            "That card is not valid on that foundation.",
+            "Cette carte ne peut pas aller sur cette fondation.",
+        ),
+        (
+            "You are already at the first move.",
+            "Vous êtes déjà au premier coup.",
+        ),
+        ("That space is empty.", "Cet emplacement est vide."),
+        ("Invalid card.", "Carte non valide."),
+        ("Unknown colour mode.", "Mode de couleur inconnu."),
+        ("Unknown variant.", "Variante inconnue."),
+        ("Unknown undo mode.", "Mode d'annulation inconnu."),
+        ("Unknown language.", "Langue inconnue."),
+        (
+            "That isn't a saved game.",
+            "Ce n'est pas une partie sauvegardée.",
+        ),
+        (
+            "The saved game has a different number of times and moves.",
+            "La partie sauvegardée n'a pas autant de temps que de coups.",
+        ),
+        (
+            "That isn't a statistics file.",
+            "Ce n'est pas un fichier de statistiques.",
+        ),
+        (
+            "The statistics file has a line that can't be read.",
+            "Le fichier de statistiques a une ligne illisible.",
+        ),
+        (
+            "The scoring config has a line that can't be read.",
+            "La configuration du score a une ligne illisible.",
+        ),
+        (
+            "The scoring config has a setting that doesn't exist.",
+            "La configuration du score a un réglage qui n'existe pas.",
+        ),
+        (
+            "The saved game has a line that can't be read.",
+            "La partie sauvegardée contient une ligne illisible.",
+        ),
+        (
+            "The saved game doesn't have exactly one of each card.",
+            "La partie sauvegardée ne contient pas exactement une carte de chaque.",
+        ),
+        (
+            "The saved game has a move that can't be played.",
+            "La partie sauvegardée contient un coup impossible à jouer.",
+        ),
+        // Descriptions of the board
+        ("cascade {}", "colonne {}"),
+        ("free cell {}", "cellule {}"),
+        ("foundation {}", "fondation {}"),
+        ("Free cells: {}.", "Cellules libres : {}."),
+        ("Foundations: {}.", "Fondations : {}."),
+        ("{} holds {}", "{} contient {}"),
+        ("{} empty", "{} vide"),
+        ("Cascade {}: {}.", "Colonne {} : {}."),
+        ("Cascade {}: empty.", "Colonne {} : vide."),
+        ("{} (selected)", "{} (sélectionnée)"),
+        ("{} (highlighted)", "{} (en surbrillance)"),
+        ("Moved {}.", "Déplacé : {}."),
+        ("Took back moving {}.", "Annulé : {}."),
+        ("the {} from {} to {}", "{}, de {} vers {}"),
+        (
+            "{} cards, {} down to {}, from {} to {}",
+            "{} cartes, de {} à {}, de {} vers {}",
+        ),
+        (
+            "Cascade {} now ends with {}.",
+            "La colonne {} se termine maintenant par {}.",
+        ),
+        (
+            "Cascade {} is now empty.",
+            "La colonne {} est maintenant vide.",
+        ),
+        // Answers to yes or no questions
+        ("y", "o"),
+        ("n", "n"),
+        // Resuming a game after the program was closed
+        (
+            "Carry on with the game left unfinished last time? ({}/{})",
+            "Reprendre la partie laissée inachevée la dernière fois ? ({}/{})",
+        ),
+        (
+            "Couldn't read the unfinished game in {}: {}",
+            "Impossible de lire la partie inachevée dans {} : {}",
+        ),
+        (
+            "Couldn't save the game for next time: {}",
+            "Impossible de garder la partie pour la prochaine fois : {}",
+        ),
+        // Statistics
+        (
+            "Show the score for this game so far",
+            "Afficher le score de cette partie jusqu'ici",
+        ),
+        (
+            "Show the time taken, and when each split was reached",
+            "Afficher le temps écoulé, et quand chaque étape a été atteinte",
+        ),
+        ("Time: {}  Score: {}", "Temps : {}  Score : {}"),
+        (
+            "Show your record over every game you've finished",
+            "Afficher votre bilan sur toutes les parties terminées",
+        ),
+        ("none yet", "pas encore"),
+        ("Games played: {}", "Parties jouées : {}"),
+        ("Won: {} ({}%)", "Gagnées : {} ({} %)"),
+        ("Abandoned: {}", "Abandonnées : {}"),
+        ("Current streak: {}", "Série en cours : {}"),
+        ("Best streak: {}", "Meilleure série : {}"),
+        ("Average moves per win: {}", "Coups en moyenne par victoire : {}"),
+        ("Average time per win: {}", "Temps moyen par victoire : {}"),
+        ("Best score: {}", "Meilleur score : {}"),
+        ("Average score per win: {}", "Score moyen par victoire : {}"),
+        ("Score: {}", "Score : {}"),
+        (
+            "Couldn't read the scoring config {}: {}",
+            "Impossible de lire la configuration du score {} : {}",
+        ),
+        (
+            "Couldn't update the statistics: {}",
+            "Impossible de mettre à jour les statistiques : {}",
+        ),
+        (
+            "Couldn't read the statistics: {}",
+            "Impossible de lire les statistiques : {}",
+        ),
+        // The game clock
+        ("First foundation card", "Première carte en fondation"),
+        ("Suit complete: {}", "Famille complète : {}"),
+        ("Won", "Gagné"),
+        ("Time: {}", "Temps : {}"),
+        ("Last move: {}", "Dernier coup : {}"),
+        ("{} at {}", "{} à {}"),
+        // Undo modes
+        (
+            "Undo is turned off for this game.",
+            "L'annulation est désactivée pour cette partie.",
+        ),
+        (
+            "There are no undos left for this game.",
+            "Il ne reste plus d'annulations pour cette partie.",
+        ),
+        ("Undos left: {}", "Annulations restantes : {}"),
+        ("Undos are free", "Annulations gratuites"),
+        ("Undos cost points", "Annulations payantes en points"),
+        ("Only 1 undo", "Une seule annulation"),
+        ("Only {} undos", "Seulement {} annulations"),
+        ("No undos", "Aucune annulation"),
+        // The CLI
+        (
+            "Usage: cli [options]
+
+Options:
+  --deal <number>   Play a numbered deal, the same as in Microsoft FreeCell
+  --seed <number>   Play a shuffled deal that's the same every time for a given seed
+  --variant <name>  Rules to play by: freecell (the default) or bakers-game
+  --undo <mode>     costly (the default) to take points off for each undo, free,
+                    off, or the number of undos allowed
+  --colour <mode>   auto (the default), plain, monochrome, 16, 256 or truecolour
+  --language <code> Language for messages and card names: en or fr (the default
+                    comes from LANG)
+  --describe        Describe the board in words instead of drawing it, and read
+                    out each move, for screen readers
+  --autoplay        Move cards to the foundations once nothing else can use them
+  --no-autoplay     Leave every move to the player (the default)
+  --load <file>     Carry on with a game saved with \"save <file>\"
+  --replay <file>   Play the moves listed in a file before handing over
+  --batch <file>    Play a script of moves without any input, then exit with
+                    success only if the game was won (\"-\" reads standard input)
+  -h, --help        Show this message
+  -V, --version     Show the version number
+",
+            "Utilisation : cli [options]
+
+Options :
+  --deal <numéro>    Jouer une donne numérotée, la même que dans Microsoft FreeCell
+  --seed <nombre>    Jouer une donne mélangée, toujours la même pour une graine
+  --variant <nom>    Règles du jeu : freecell (par défaut) ou bakers-game
+  --undo <mode>      costly (par défaut) pour retirer des points à chaque
+                     annulation, free, off, ou le nombre d'annulations permises
+  --colour <mode>    auto (par défaut), plain, monochrome, 16, 256 ou truecolour
+  --language <code>  Langue des messages et des noms de cartes : en ou fr (par
+                     défaut, celle de LANG)
+  --describe         Décrire le plateau en toutes lettres au lieu de le dessiner,
+                     et annoncer chaque coup, pour les lecteurs d'écran
+  --autoplay         Monter les cartes sur les fondations dès qu'elles ne servent
+                     plus à rien d'autre
+  --no-autoplay      Laisser tous les coups au joueur (par défaut)
+  --load <fichier>   Reprendre une partie sauvegardée avec \"save <fichier>\"
+  --replay <fichier> Jouer les coups d'un fichier avant de rendre la main
+  --batch <fichier>  Jouer un script de coups sans rien demander, puis terminer
+                     avec succès seulement si la partie est gagnée (\"-\" lit
+                     l'entrée standard)
+  -h, --help         Afficher ce message
+  -V, --version      Afficher le numéro de version
+",
+        ),
+        (
+            "Type the character for the source position followed by the destination position.
+For instance, to move from the third column to the first free cell, type \"3a\".
+Type \"help\" for the list of commands, such as \"hint\", \"undo\" and \"new\".",
+            "Tapez le caractère de la position de départ suivi de celui de la destination.
+Par exemple, pour aller de la troisième colonne à la première cellule libre, tapez \"3a\".
+Tapez \"help\" pour la liste des commandes, comme \"hint\", \"undo\" et \"new\".",
+        ),
+        (
+            "Moves are typed as the source followed by the destination: 1-8 for the cascades,
+a-d for the free cells and w-z for the foundations. To move a stack to an empty
+cascade, put the number of cards and \"+\" in front, as in \"3+15\", or just \"+\" for
+as many as possible.
+",
+            "Un coup se tape comme la source suivie de la destination : 1-8 pour les colonnes,
+a-d pour les cellules libres et w-z pour les fondations. Pour déplacer une pile vers
+une colonne vide, faites précéder le coup du nombre de cartes et de \"+\", comme dans
+\"3+15\", ou simplement de \"+\" pour en déplacer le plus possible.
+",
+        ),
+        ("[deal]", "[donne]"),
+        ("<file>", "<fichier>"),
+        (
+            "Start a new game, with a random deal or a numbered one",
+            "Commencer une nouvelle partie, avec une donne au hasard ou numérotée",
+        ),
+        (
+            "Go back to the start of this deal",
+            "Revenir au début de cette donne",
+        ),
+        ("Take back the last move", "Annuler le dernier coup"),
+        ("Show the board again", "Afficher à nouveau le plateau"),
+        ("Suggest a move", "Suggérer un coup"),
+        (
+            "List every move that can be played",
+            "Lister tous les coups possibles",
+        ),
+        (
+            "List the moves played so far",
+            "Lister les coups déjà joués",
+        ),
+        (
+            "Save the game, to carry on later with --load",
+            "Sauvegarder la partie, pour la reprendre plus tard avec --load",
+        ),
+        (
+            "Change which cards are highlighted",
+            "Changer les cartes mises en surbrillance",
+        ),
+        (
+            "Switch between two and four suit colours",
+            "Passer de deux à quatre couleurs de familles",
+        ),
+        (
+            "Switch between text and compact card glyphs",
+            "Passer du texte aux symboles de cartes compacts",
+        ),
+        ("Show this list", "Afficher cette liste"),
+        ("Stop playing", "Arrêter de jouer"),
+        (
+            "The deal has to be a number.",
+            "La donne doit être un nombre.",
+        ),
+        ("Try {}.", "Essayez {}."),
+        (
+            "There's nothing useful left to play. Try undoing, or start a new game.",
+            "Il ne reste rien d'utile à jouer. Annulez un coup, ou commencez une nouvelle partie.",
+        ),
+        (
+            "There are no moves left.",
+            "Il n'y a plus aucun coup possible.",
+        ),
+        (
+            "No moves have been played yet.",
+            "Aucun coup n'a encore été joué.",
+        ),
+        (
+            "Type a file name after \"save\".",
+            "Tapez un nom de fichier après \"save\".",
+        ),
+        ("Saved to {}.", "Partie sauvegardée dans {}."),
+        (
+            "Couldn't save to {}: {}",
+            "Impossible de sauvegarder dans {} : {}",
+        ),
+        (
+            "The locale isn't UTF-8, so cards stay as text.",
+            "La locale n'est pas en UTF-8, les cartes restent en texte.",
+        ),
+        (
+            "You win! Type \"new\" to play again, or \"quit\" to stop.",
+            "Gagné ! Tapez \"new\" pour rejouer, ou \"quit\" pour arrêter.",
+        ),
+        (
+            "This game isn't finished. Abandon it? ({}/{})",
+            "Cette partie n'est pas terminée. L'abandonner ? ({}/{})",
+        ),
+        ("Couldn't read {}: {}", "Impossible de lire {} : {}"),
+        (
+            "Couldn't read the script: {}",
+            "Impossible de lire le script : {}",
+        ),
+        ("Couldn't load {}: {}", "Impossible de charger {} : {}"),
+        (
+            "The script chooses a deal, but a game was already loaded",
+            "Le script choisit une donne, mais une partie a déjà été chargée",
+        ),
+        ("Move {} in {} ({}): {}", "Coup {} dans {} ({}) : {}"),
+        ("Move {}: {}", "Coup {} : {}"),
+        ("{} needs a value", "{} attend une valeur"),
+        ("{} needs a number", "{} attend un nombre"),
+        (
+            "Only one of --deal and --seed can be given",
+            "--deal et --seed ne peuvent pas être donnés ensemble",
+        ),
+        ("Unknown option: {}", "Option inconnue : {}"),
+        (
+            "A loaded game already has its deal and variant",
+            "Une partie chargée a déjà sa donne et sa variante",
+        ),
+        (
+            "A loaded game already has its undo mode",
+            "Une partie chargée a déjà son mode d'annulation",
+        ),
+        (
+            "A batch script can't be combined with --replay",
+            "Un script ne peut pas être combiné avec --replay",
+        ),
+        ("Line {}: {}", "Ligne {} : {}"),
+        ("Line {}, {}: {}", "Ligne {}, {} : {}"),
+        (
+            "The deal has already been chosen",
+            "La donne a déjà été choisie",
+        ),
+        ("Invalid deal number", "Numéro de donne non valide"),
+        ("Invalid seed", "Graine non valide"),
+        ("Unknown setting", "Réglage inconnu"),
+        (
+            "{} of {} moves couldn't be played:",
+            "{} coups sur {} n'ont pas pu être joués :",
+        ),
+        ("Won in 1 move.", "Gagné en 1 coup."),
+        ("Won in {} moves.", "Gagné en {} coups."),
+        (
+            "Not won: {} cards are still to be played.",
+            "Pas gagné : il reste {} cartes à jouer.",
+        ),
+        // The TUI
+        (
+            "Usage: tui [--record <file>] [--language <code>] [--undo <mode>]
+
+Options:
+  --record <file>    Save the session as an asciicast v2 recording
+  --language <code>  Language for messages: en or fr (the default comes from LANG)
+  --undo <mode>      costly (the default) to take points off for each undo, free,
+                     off, or the number of undos allowed
+  -h, --help         Show this message
+",
+            "Utilisation : tui [--record <fichier>] [--language <code>] [--undo <mode>]
+
+Options :
+  --record <fichier> Enregistrer la session au format asciicast v2
+  --language <code>  Langue des messages : en ou fr (par défaut, celle de LANG)
+  --undo <mode>      costly (par défaut) pour retirer des points à chaque
+                     annulation, free, off, ou le nombre d'annulations permises
+  -h, --help         Afficher ce message
+",
+        ),
+        ("Esc", "Échap"),
+        ("Click", "Clic"),
+        ("Start a new random deal", "Nouvelle donne au hasard"),
+        ("Restart this deal", "Recommencer cette donne"),
+        ("Choose a deal by number", "Choisir une donne par son numéro"),
+        ("Back to the game", "Retour à la partie"),
+        ("Undo the last move", "Annuler le dernier coup"),
+        ("Replay the game so far", "Revoir la partie depuis le début"),
+        ("Switch colour theme", "Changer de thème de couleurs"),
+        ("Cancel the move being entered", "Abandonner le coup en cours de saisie"),
+        (
+            "New game, restart, or choose a deal",
+            "Nouvelle partie, recommencer, ou choisir une donne",
+        ),
+        (
+            "Show the time taken and the splits",
+            "Afficher le temps écoulé et les étapes",
+        ),
+        ("Show your statistics", "Afficher vos statistiques"),
+        ("Show this screen", "Afficher cet écran"),
+        ("Quit", "Quitter"),
+        (
+            "Cascades, as a source or destination",
+            "Colonnes, comme source ou destination",
+        ),
+        (
+            "Free cells, as a source or destination",
+            "Cellules libres, comme source ou destination",
+        ),
+        ("Foundations, as a destination", "Fondations, comme destination"),
+        (
+            "Before a move, move as many cards as possible",
+            "Avant un coup, déplacer le plus de cartes possible",
+        ),
+        (
+            "Select a pile, then click where it should go",
+            "Choisir une pile, puis cliquer là où elle doit aller",
+        ),
+        (
+            "Build the foundations up by suit from ace to king.
+Cascades build down in alternating colours. Any card
+can go in a free cell or an empty cascade. Stacks can
+be moved when there is enough free space to do so
+one card at a time.",
+            "Montez les fondations par famille, de l'as au roi.
+Les colonnes descendent en alternant les couleurs.
+Toute carte peut aller dans une cellule libre ou une
+colonne vide. Une pile peut être déplacée s'il y a
+assez de place pour le faire une carte à la fois.",
+        ),
+        ("Theme: {}", "Thème : {}"),
+        ("Deal number: {}", "Numéro de donne : {}"),
+        (
+            "Enter to start, Esc to cancel",
+            "Entrée pour commencer, Échap pour annuler",
+        ),
+        (
+            " Deal #{}  Moves: {}  Time: {}  Score: {}  Free cells: {}  Empty cascades: {}  Max stack: {}",
+            " Donne n° {}  Coups : {}  Temps : {}  Score : {}  Cellules libres : {}  Colonnes vides : {}  Pile max : {}",
+        ),
+        ("Press ? for help", "Appuyez sur ? pour l'aide"),
+        // The GUI
+        ("New game", "Nouvelle partie"),
+        ("Restart", "Recommencer"),
+        ("Undo", "Annuler"),
+        ("Redo", "Rétablir"),
+        ("Deal #{}", "Donne n° {}"),
+        ("You win!", "Gagné !"),
+        ("There is nothing to redo.", "Il n'y a rien à rétablir."),
+    ],
+    cells: ['a', 'b', 'c', 'd'],
+    foundations: ['w', 'x', 'y', 'z'],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_locale() {
+        assert_eq!(Some(Language::French), Language::from_locale("fr_FR.UTF-8"));
+        assert_eq!(Some(Language::French), Language::from_locale("fr"));
+        assert_eq!(Some(Language::English), Language::from_locale("en_GB"));
+        assert_eq!(None, Language::from_locale("C"));
+        assert_eq!(None, Language::from_locale("de_DE@euro"));
+    }
+
+    #[test]
+    fn from_str() {
+        for language in Language::ALL {
+            assert_eq!(Ok(language), language.to_string().parse());
+        }
+        assert_eq!(Ok(Language::French), "FR".parse());
+        assert_eq!(Err("Unknown language."), "klingon".parse::<Language>());
+    }
+
+    #[test]
+    fn tr() {
+        assert_eq!(
+            "That space is empty.",
+            Language::English.tr("That space is empty.")
+        );
+        assert_eq!(
+            "Cet emplacement est vide.",
+            Language::French.tr("That space is empty.")
+        );
+        assert_eq!(
+            "Not in any catalog",
+            Language::French.tr("Not in any catalog")
+        );
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(
+            "Cascade 3: empty.",
+            Language::English.format("Cascade {}: empty.", &[&3])
+        );
+        assert_eq!(
+            "A contient 7 de pique",
+            Language::French.format("{} holds {}", &[&'A', &"7 de pique"]),
+        );
+        assert_eq!("Moved .", Language::English.format("Moved {}.", &[]));
+    }
+
+    #[test]
+    fn card_name() {
+        let card = Card::new(Rank::Queen, Suit::Hearts);
+
+        assert_eq!("Queen of hearts", Language::English.card_name(&card));
+        assert_eq!(
+            "10 of diamonds",
+            Language::English.card_name(&Card::new(Rank::Ten, Suit::Diamonds)),
+        );
+        assert_eq!("Dame de cœur", Language::French.card_name(&card));
+        assert_eq!(
+            "7 de pique",
+            Language::French.card_name(&Card::new(Rank::Seven, Suit::Spades)),
+        );
+    }
+
+    #[test]
+    fn actions() {
+        let action: Action = "15+78".parse().unwrap();
+
+        for language in Language::ALL {
+            assert_eq!(Ok(&action), language.parse_action("15+78").as_ref());
+            assert_eq!("15+78", language.action_string(&action));
+            assert_eq!("3w", language.action_string(&"3W".parse().unwrap()));
+        }
+        assert_eq!(
+            Err("Saisie non valide."),
+            Language::French.parse_action("3e")
+        );
+    }
+
+    #[test]
+    fn catalogs_match_their_templates() {
+        for language in Language::ALL {
+            for (english, translation) in language.catalog().messages {
+                assert_eq!(
+                    english.matches("{}").count(),
+                    translation.matches("{}").count(),
+                    "{}",
+                    english,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,813 @@
+use super::{
+    Action, Card, CardStyle, DisplayOptions, FromCoordinate, Language, Single, SuitColour, Tableau,
+    ToCoordinate,
+};
+use std::fmt::{self, Write};
+
+/// Everything a renderer needs to know about how the player is looking at the tableau.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct View {
+    pub options: DisplayOptions,
+
+    /// The pile the player has picked, if they're partway through choosing a move.
+    pub selected: Option<FromCoordinate>,
+}
+
+impl View {
+    /// Whether the card at `depth` in a pile should stand out, either because it's highlighted
+    /// or because it's the top card of the selected pile.
+    pub fn is_marked(&self, tableau: &Tableau, from: FromCoordinate, depth: usize) -> bool {
+        self.is_selected(tableau, from, depth)
+            || tableau.is_highlighted(from, depth, self.options.highlight)
+    }
+
+    /// Whether the card at `depth` in a pile is the top card of the selected pile.
+    pub fn is_selected(&self, tableau: &Tableau, from: FromCoordinate, depth: usize) -> bool {
+        let top = match from {
+            FromCoordinate::Cascade(n) => tableau.cascades[n as usize].len().checked_sub(1),
+            FromCoordinate::Cell(n) => tableau.cells[n as usize].peek().map(|_| 0),
+        };
+
+        self.selected == Some(from) && top == Some(depth)
+    }
+}
+
+/// Turns a tableau into output for a particular kind of frontend, so that new frontends can share
+/// the same view state without copying each other's layout code.
+pub trait Renderer {
+    type Output;
+
+    fn render(&mut self, tableau: &Tableau, view: &View) -> Self::Output;
+}
+
+/// Renders a tableau as text for a terminal, using as much colour as the view's options allow,
+/// in either the full text layout or the compact glyph layout.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AnsiRenderer;
+
+impl Renderer for AnsiRenderer {
+    type Output = String;
+
+    fn render(&mut self, tableau: &Tableau, view: &View) -> String {
+        let mut output = String::new();
+
+        match view.options.cards {
+            CardStyle::Text => write_text(&mut output, tableau, view),
+            CardStyle::Glyph => write_glyphs(&mut output, tableau, view),
+        }
+        .unwrap();
+
+        output
+    }
+}
+
+/// Renders a tableau in plain 7-bit ASCII, for logs, email and anywhere else that escape codes or
+/// Unicode suit symbols won't survive. Cards are written as a rank and suit letter, such as `TH`
+/// for the ten of hearts. Highlighted cards are marked with `*` and the selected card with `[]`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsciiRenderer;
+
+impl Renderer for AsciiRenderer {
+    type Output = String;
+
+    fn render(&mut self, tableau: &Tableau, view: &View) -> String {
+        let card_string = |from: Option<FromCoordinate>, depth: usize, card: Option<&Card>| {
+            let (left, right) = match from {
+                Some(from) if view.is_selected(tableau, from, depth) => ('[', ']'),
+                Some(from) if view.is_marked(tableau, from, depth) => ('*', '*'),
+                _ => (' ', ' '),
+            };
+
+            match card {
+                Some(card) => format!(
+                    "{}{}{}{}",
+                    left,
+                    card.get_rank().as_char(),
+                    card.get_suit().as_letter(),
+                    right
+                ),
+                None => " -- ".to_string(),
+            }
+        };
+
+        let mut lines = vec![" A   B   C   D   W   X   Y   Z".to_string()];
+
+        lines.push(
+            tableau
+                .cells
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| card_string(Some(FromCoordinate::Cell(i as u8)), 0, cell.peek()))
+                .chain(
+                    tableau
+                        .foundations
+                        .iter()
+                        .map(|foundation| card_string(None, 0, foundation.peek())),
+                )
+                .collect(),
+        );
+
+        lines.push(String::new());
+        lines.push(" 1   2   3   4   5   6   7   8".to_string());
+
+        let longest_cascade = tableau
+            .cascades
+            .iter()
+            .map(|cascade| cascade.len())
+            .max()
+            .unwrap_or(0);
+
+        lines.extend((0..longest_cascade).map(|row| {
+            tableau
+                .cascades
+                .iter()
+                .enumerate()
+                .map(|(i, cascade)| match cascade.cards().get(row) {
+                    Some(card) => {
+                        card_string(Some(FromCoordinate::Cascade(i as u8)), row, Some(card))
+                    }
+                    None => "    ".to_string(),
+                })
+                .collect()
+        }));
+
+        lines
+            .iter()
+            .map(|line| format!("{}\n", line.trim_end()))
+            .collect()
+    }
+}
+
+/// Describes a tableau in plain sentences, one line per row of piles, for screen readers and
+/// anywhere else the board is heard rather than seen. Cascades are read from the bottom card to
+/// the top one, so the card that can be moved comes last. Colour and card style are ignored, but
+/// highlighted and selected cards are called out.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DescriptionRenderer;
+
+impl DescriptionRenderer {
+    /// What a move did, such as "Moved the 7 of spades from cascade 3 to free cell A.", followed
+    /// by the card it uncovered. `None` if `after` isn't one move on from `before`.
+    pub fn describe_move(
+        &self,
+        before: &Tableau,
+        after: &Tableau,
+        language: Language,
+    ) -> Option<String> {
+        let action = before.move_to(after)?;
+        let mut sentences = vec![language.format(
+            "Moved {}.",
+            &[&Self::move_phrase(before, &action, language)?],
+        )];
+
+        if let Action::MoveCard {
+            from: FromCoordinate::Cascade(n),
+            ..
+        } = action
+        {
+            sentences.push(match after.cascades[n as usize].cards().last() {
+                Some(card) => language.format(
+                    "Cascade {} now ends with {}.",
+                    &[&(n + 1), &language.card_name(card)],
+                ),
+                None => language.format("Cascade {} is now empty.", &[&(n + 1)]),
+            });
+        }
+
+        Some(sentences.join(" "))
+    }
+
+    /// What taking back a move did, given the tableau before and after the undo.
+    pub fn describe_undo(
+        &self,
+        before: &Tableau,
+        after: &Tableau,
+        language: Language,
+    ) -> Option<String> {
+        let action = after.move_to(before)?;
+        Some(language.format(
+            "Took back moving {}.",
+            &[&Self::move_phrase(after, &action, language)?],
+        ))
+    }
+
+    /// The cards `action` moves and where they go, as played on `tableau`.
+    fn move_phrase(tableau: &Tableau, action: &Action, language: Language) -> Option<String> {
+        let Action::MoveCard { from, to, count } = *action else {
+            return None;
+        };
+        let cards: &[Card] = match from {
+            FromCoordinate::Cascade(n) => tableau.cascades[n as usize].cards(),
+            FromCoordinate::Cell(n) => tableau.cells[n as usize]
+                .peek()
+                .map_or(&[], |card| std::slice::from_ref(card)),
+        };
+        let count = count.map_or(1, |n| n.get() as usize);
+        let moved = &cards[cards.len().saturating_sub(count)..];
+        let (from, to) = (language.pile_name(from.into()), language.pile_name(to));
+
+        match moved {
+            [card] => Some(language.format(
+                "the {} from {} to {}",
+                &[&language.card_name(card), &from, &to],
+            )),
+            [first, .., last] => Some(language.format(
+                "{} cards, {} down to {}, from {} to {}",
+                &[
+                    &moved.len(),
+                    &language.card_name(first),
+                    &language.card_name(last),
+                    &from,
+                    &to,
+                ],
+            )),
+            [] => None,
+        }
+    }
+}
+
+impl Renderer for DescriptionRenderer {
+    type Output = String;
+
+    fn render(&mut self, tableau: &Tableau, view: &View) -> String {
+        let language = view.options.language;
+        let card_name = |from: FromCoordinate, depth: usize, card: &Card| {
+            let name = language.card_name(card);
+            if view.is_selected(tableau, from, depth) {
+                language.format("{} (selected)", &[&name])
+            } else if view.is_marked(tableau, from, depth) {
+                language.format("{} (highlighted)", &[&name])
+            } else {
+                name
+            }
+        };
+        let pile = |pile: ToCoordinate, card: Option<String>| {
+            let label = language.pile_letter(pile).unwrap().to_ascii_uppercase();
+            match card {
+                Some(card) => language.format("{} holds {}", &[&label, &card]),
+                None => language.format("{} empty", &[&label]),
+            }
+        };
+        let mut lines = Vec::new();
+
+        let cells: Vec<String> = (0..4)
+            .map(|i| {
+                let card = tableau.cells[i as usize]
+                    .peek()
+                    .map(|card| card_name(FromCoordinate::Cell(i), 0, card));
+                pile(ToCoordinate::Cell(i), card)
+            })
+            .collect();
+        lines.push(language.format("Free cells: {}.", &[&cells.join(", ")]));
+
+        let foundations: Vec<String> = (0..4)
+            .map(|i| {
+                let card = tableau.foundations[i as usize]
+                    .peek()
+                    .map(|card| language.card_name(card));
+                pile(ToCoordinate::Foundation(i), card)
+            })
+            .collect();
+        lines.push(language.format("Foundations: {}.", &[&foundations.join(", ")]));
+
+        lines.extend(tableau.cascades.iter().enumerate().map(|(i, cascade)| {
+            let from = FromCoordinate::Cascade(i as u8);
+            let cards: Vec<String> = cascade
+                .cards()
+                .iter()
+                .enumerate()
+                .map(|(depth, card)| card_name(from, depth, card))
+                .collect();
+
+            match cards.as_slice() {
+                [] => language.format("Cascade {}: empty.", &[&(i + 1)]),
+                cards => language.format("Cascade {}: {}.", &[&(i + 1), &cards.join(", ")]),
+            }
+        }));
+
+        lines.iter().map(|line| format!("{}\n", line)).collect()
+    }
+}
+
+/// Renders a tableau as a standalone SVG document, for attaching pictures of the board to bug
+/// reports and puzzle write-ups. Colour support is ignored, since the image isn't bound for a
+/// terminal, but the palette, highlighting and selection are all drawn.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SvgRenderer;
+
+impl SvgRenderer {
+    const CARD_WIDTH: u32 = 60;
+    const CARD_HEIGHT: u32 = 84;
+    const GAP: u32 = 10;
+    const LABEL_HEIGHT: u32 = 20;
+    const FAN_OFFSET: u32 = 24;
+
+    fn column_x(column: u32) -> u32 {
+        Self::GAP + column * (Self::CARD_WIDTH + Self::GAP)
+    }
+
+    fn card(svg: &mut String, card: &Card, (x, y): (u32, u32), view: &View, marked: bool) {
+        let fill = match card.get_suit().colour(view.options.palette) {
+            SuitColour::Black => "#000000",
+            SuitColour::Red => "#c62828",
+            SuitColour::Blue => "#1565c0",
+            SuitColour::Green => "#2e7d32",
+        };
+        let (stroke, stroke_width) = if marked {
+            ("#ffca28", 3)
+        } else {
+            ("#424242", 1)
+        };
+        let (width, height) = (Self::CARD_WIDTH, Self::CARD_HEIGHT);
+
+        writeln!(
+            svg,
+            r##"<g class="card" transform="translate({x} {y})" fill="{fill}">
+<rect width="{width}" height="{height}" rx="5" fill="#ffffff" stroke="{stroke}" stroke-width="{stroke_width}"/>
+<text x="5" y="16" font-size="14">{rank}</text>
+<text x="5" y="30" font-size="14">{suit}</text>
+<text x="{centre_x}" y="{centre_y}" font-size="28" text-anchor="middle" dominant-baseline="central">{suit}</text>
+<text transform="rotate(180 {centre_x} {centre_y})" x="5" y="16" font-size="14">{rank}</text>
+<text transform="rotate(180 {centre_x} {centre_y})" x="5" y="30" font-size="14">{suit}</text>
+</g>"##,
+            rank = card.get_rank(),
+            suit = card.get_suit(),
+            centre_x = width / 2,
+            centre_y = height / 2,
+        )
+        .unwrap();
+    }
+
+    fn empty(svg: &mut String, (x, y): (u32, u32)) {
+        writeln!(
+            svg,
+            r##"<rect class="empty" x="{x}" y="{y}" width="{}" height="{}" rx="5" fill="none" stroke="#a5d6a7" stroke-dasharray="4 3"/>"##,
+            Self::CARD_WIDTH,
+            Self::CARD_HEIGHT,
+        )
+        .unwrap();
+    }
+
+    fn label(svg: &mut String, label: char, column: u32, y: u32) {
+        writeln!(
+            svg,
+            r##"<text x="{}" y="{}" font-size="14" fill="#e8f5e9" text-anchor="middle">{}</text>"##,
+            Self::column_x(column) + Self::CARD_WIDTH / 2,
+            y + Self::LABEL_HEIGHT - 6,
+            label,
+        )
+        .unwrap();
+    }
+}
+
+impl Renderer for SvgRenderer {
+    type Output = String;
+
+    fn render(&mut self, tableau: &Tableau, view: &View) -> String {
+        let longest_cascade = tableau
+            .cascades
+            .iter()
+            .map(|cascade| cascade.len() as u32)
+            .max()
+            .unwrap_or(0);
+
+        let top_row_y = Self::GAP + Self::LABEL_HEIGHT;
+        let cascades_label_y = top_row_y + Self::CARD_HEIGHT + Self::GAP;
+        let cascades_y = cascades_label_y + Self::LABEL_HEIGHT;
+        let width = Self::column_x(8);
+        let height = cascades_y
+            + Self::CARD_HEIGHT
+            + longest_cascade.saturating_sub(1) * Self::FAN_OFFSET
+            + Self::GAP;
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif">
+<rect width="100%" height="100%" fill="#1b5e20"/>"##,
+        )
+        .unwrap();
+
+        for (column, label) in ('A'..='D').chain('W'..='Z').enumerate() {
+            Self::label(&mut svg, label, column as u32, Self::GAP);
+        }
+
+        for (column, label) in ('1'..='8').enumerate() {
+            Self::label(&mut svg, label, column as u32, cascades_label_y);
+        }
+
+        let top_row = tableau
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (Some(FromCoordinate::Cell(i as u8)), cell.peek()))
+            .chain(
+                tableau
+                    .foundations
+                    .iter()
+                    .map(|foundation| (None, foundation.peek())),
+            );
+
+        for (column, (from, card)) in top_row.enumerate() {
+            let pos = (Self::column_x(column as u32), top_row_y);
+            let marked = from.is_some_and(|from| view.is_marked(tableau, from, 0));
+
+            match card {
+                Some(card) => Self::card(&mut svg, card, pos, view, marked),
+                None => Self::empty(&mut svg, pos),
+            }
+        }
+
+        for (column, cascade) in tableau.cascades.iter().enumerate() {
+            let from = FromCoordinate::Cascade(column as u8);
+            let x = Self::column_x(column as u32);
+
+            if cascade.is_empty() {
+                Self::empty(&mut svg, (x, cascades_y));
+            }
+
+            for (depth, card) in cascade.cards().iter().enumerate() {
+                let pos = (x, cascades_y + depth as u32 * Self::FAN_OFFSET);
+                let marked = view.is_marked(tableau, from, depth);
+                Self::card(&mut svg, card, pos, view, marked);
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// One glyph per card, so that the whole tableau fits in a narrow terminal.
+fn write_glyphs(f: &mut String, tableau: &Tableau, view: &View) -> fmt::Result {
+    let options = view.options;
+
+    let card_string = |from: Option<FromCoordinate>, depth: usize, card: Option<&Card>| {
+        let highlighted = from.is_some_and(|from| view.is_marked(tableau, from, depth));
+
+        match card {
+            Some(card) => card.glyph_face(options.palette, options.colours, highlighted),
+            None if options.colours.has_escapes() => "\x1b[2m\u{1f0a0}\x1b[0m".to_string(),
+            None => "\u{1f0a0}".to_string(),
+        }
+    };
+
+    writeln!(f, "A B C D W X Y Z")?;
+    tableau
+        .cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| card_string(Some(FromCoordinate::Cell(i as u8)), 0, cell.peek()))
+        .chain(
+            tableau
+                .foundations
+                .iter()
+                .map(|foundation| card_string(None, 0, foundation.peek())),
+        )
+        .try_for_each(|s| write!(f, "{} ", s))?;
+    writeln!(f)?;
+    writeln!(f)?;
+
+    writeln!(f, "1 2 3 4 5 6 7 8")?;
+
+    let longest_cascade = tableau
+        .cascades
+        .iter()
+        .map(|cascade| cascade.len())
+        .max()
+        .unwrap_or(0);
+
+    for row in 0..longest_cascade {
+        tableau
+            .cascades
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, cascade)| match cascade.cards().get(row) {
+                Some(card) => {
+                    let from = Some(FromCoordinate::Cascade(i as u8));
+                    write!(f, "{} ", card_string(from, row, Some(card)))
+                }
+                None => write!(f, "  "),
+            })?;
+        writeln!(f)?;
+    }
+
+    Ok(())
+}
+
+fn write_text(f: &mut String, tableau: &Tableau, view: &View) -> fmt::Result {
+    let options = view.options;
+
+    let card_string = |from: FromCoordinate, depth: usize, card: &Card| {
+        card.face(
+            options.palette,
+            options.colours,
+            view.is_marked(tableau, from, depth),
+        )
+    };
+
+    // cells and foundations
+    {
+        writeln!(f, " A   B   C   D   W   X   Y   Z")?;
+        let top_row: Vec<String> = tableau
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                cell.peek()
+                    .map(|card| card_string(FromCoordinate::Cell(i as u8), 0, card))
+            })
+            .chain(tableau.foundations.iter().map(|foundation| {
+                foundation
+                    .peek()
+                    .map(|card| card.face(options.palette, options.colours, false))
+            }))
+            .map(|card| {
+                card.unwrap_or_else(|| {
+                    if options.colours.has_escapes() {
+                        "\x1b[2;7m   \x1b[0m\n\x1b[2;7m   \x1b[0m".to_string()
+                    } else {
+                        "[ ]\n   ".to_string()
+                    }
+                })
+            })
+            .collect();
+
+        top_row
+            .iter()
+            .try_for_each(|s| write!(f, "{} ", s.lines().next().unwrap()))?;
+        writeln!(f)?;
+
+        top_row
+            .iter()
+            .try_for_each(|s| write!(f, "{} ", s.lines().last().unwrap()))?;
+        writeln!(f)?;
+    }
+
+    writeln!(f)?;
+
+    // cascades
+    {
+        writeln!(f, " 1   2   3   4   5   6   7   8")?;
+
+        let longest_cascade = tableau
+            .cascades
+            .iter()
+            .map(|cascade| cascade.len())
+            .max()
+            .unwrap_or(0);
+
+        for row in 0..longest_cascade + 1 {
+            tableau
+                .cascades
+                .iter()
+                .enumerate()
+                .try_for_each(|(i, cascade)| {
+                    let from = FromCoordinate::Cascade(i as u8);
+
+                    cascade
+                        .cards()
+                        .get(row)
+                        .map(|card| {
+                            write!(
+                                f,
+                                "{} ",
+                                card_string(from, row, card).lines().next().unwrap()
+                            )
+                        })
+                        .or_else(|| {
+                            row.checked_sub(1)
+                                .and_then(|prev_row| {
+                                    cascade.cards().get(prev_row).map(|card| (prev_row, card))
+                                })
+                                .map(|(prev_row, card)| {
+                                    write!(
+                                        f,
+                                        "{} ",
+                                        card_string(from, prev_row, card).lines().last().unwrap()
+                                    )
+                                })
+                        })
+                        .unwrap_or_else(|| write!(f, "    "))
+                })?;
+
+            writeln!(f)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColourSupport, Highlight, Rank, Suit};
+
+    fn tableau() -> Tableau {
+        let mut tableau = Tableau::empty();
+        tableau.cells[1]
+            .try_push(Card::new(Rank::Ten, Suit::Hearts))
+            .unwrap();
+        tableau.cascades[0].push(Card::new(Rank::Queen, Suit::Spades));
+        tableau.cascades[0].push(Card::new(Rank::Ace, Suit::Diamonds));
+        tableau.cascades[2].push(Card::new(Rank::Seven, Suit::Clubs));
+        tableau
+    }
+
+    #[test]
+    fn is_selected() {
+        let tableau = tableau();
+        let view = View {
+            selected: Some(FromCoordinate::Cascade(0)),
+            ..View::default()
+        };
+
+        assert!(view.is_selected(&tableau, FromCoordinate::Cascade(0), 1));
+        assert!(!view.is_selected(&tableau, FromCoordinate::Cascade(0), 0));
+        assert!(!view.is_selected(&tableau, FromCoordinate::Cascade(2), 0));
+        assert!(!View::default().is_selected(&tableau, FromCoordinate::Cascade(0), 1));
+    }
+
+    #[test]
+    fn is_marked() {
+        let tableau = tableau();
+        let view = View {
+            selected: Some(FromCoordinate::Cascade(2)),
+            ..View::default()
+        };
+
+        // The ace is highlighted for the foundation, and the seven is selected.
+        assert!(view.is_marked(&tableau, FromCoordinate::Cascade(0), 1));
+        assert!(view.is_marked(&tableau, FromCoordinate::Cascade(2), 0));
+        assert!(!view.is_marked(&tableau, FromCoordinate::Cell(1), 0));
+    }
+
+    #[test]
+    fn ascii_renderer() {
+        let view = View {
+            options: DisplayOptions {
+                highlight: Highlight::Foundation,
+                ..DisplayOptions::default()
+            },
+            selected: Some(FromCoordinate::Cell(1)),
+        };
+
+        assert_eq!(
+            " A   B   C   D   W   X   Y   Z\n \
+             -- [TH] --  --  --  --  --  --\n\
+             \n \
+             1   2   3   4   5   6   7   8\n \
+             QS      7C\n\
+             *AD*\n",
+            AsciiRenderer.render(&tableau(), &view),
+        );
+    }
+
+    #[test]
+    fn description_renderer() {
+        let view = View {
+            options: DisplayOptions {
+                highlight: Highlight::Foundation,
+                ..DisplayOptions::default()
+            },
+            selected: Some(FromCoordinate::Cell(1)),
+        };
+        let description = DescriptionRenderer.render(&tableau(), &view);
+
+        assert_eq!(
+            "Free cells: A empty, B holds 10 of hearts (selected), C empty, D empty.\n\
+             Foundations: W empty, X empty, Y empty, Z empty.\n\
+             Cascade 1: Queen of spades, Ace of diamonds (highlighted).\n\
+             Cascade 2: empty.\n",
+            description
+                .lines()
+                .take(4)
+                .map(|line| format!("{}\n", line))
+                .collect::<String>(),
+        );
+        assert_eq!(Some("Cascade 3: 7 of clubs."), description.lines().nth(4));
+        assert!(description.is_ascii());
+    }
+
+    #[test]
+    fn describe_move() {
+        let before = tableau();
+        let mut game = crate::Game::new(before.clone());
+
+        game.action("1w".parse().unwrap()).unwrap();
+        assert_eq!(
+            Some(
+                "Moved the Ace of diamonds from cascade 1 to foundation W. \
+                 Cascade 1 now ends with Queen of spades."
+                    .to_string()
+            ),
+            DescriptionRenderer.describe_move(&before, game.tableau(), Language::English),
+        );
+        assert_eq!(
+            Some(
+                "Took back moving the Ace of diamonds from cascade 1 to foundation W.".to_string()
+            ),
+            DescriptionRenderer.describe_undo(game.tableau(), &before, Language::English),
+        );
+
+        game.action("3c".parse().unwrap()).unwrap();
+        assert_eq!(
+            Some(
+                "Moved the 7 of clubs from cascade 3 to free cell C. Cascade 3 is now empty."
+                    .to_string()
+            ),
+            DescriptionRenderer.describe_move(
+                &game.history()[1],
+                game.tableau(),
+                Language::English
+            ),
+        );
+    }
+
+    #[test]
+    fn description_renderer_french() {
+        let view = View {
+            options: DisplayOptions {
+                language: Language::French,
+                ..DisplayOptions::default()
+            },
+            selected: None,
+        };
+        let description = DescriptionRenderer.render(&tableau(), &view);
+
+        assert_eq!(
+            Some("Cellules libres : A vide, B contient 10 de cœur, C vide, D vide."),
+            description.lines().next()
+        );
+        assert_eq!(
+            Some("Colonne 1 : Dame de pique, As de carreau (en surbrillance)."),
+            description.lines().nth(2)
+        );
+
+        let before = tableau();
+        let mut game = crate::Game::new(before.clone());
+        game.action("1a".parse().unwrap()).unwrap();
+        assert_eq!(
+            Some(
+                "Déplacé : As de carreau, de colonne 1 vers cellule A. \
+                 La colonne 1 se termine maintenant par Dame de pique."
+                    .to_string()
+            ),
+            DescriptionRenderer.describe_move(&before, game.tableau(), Language::French),
+        );
+    }
+
+    #[test]
+    fn describe_stack_move() {
+        let mut before = Tableau::empty();
+        before.cascades[0].push(Card::new(Rank::Nine, Suit::Hearts));
+        before.cascades[0].push(Card::new(Rank::Eight, Suit::Clubs));
+        before.cascades[0].push(Card::new(Rank::Seven, Suit::Diamonds));
+        let mut game = crate::Game::new(before.clone());
+
+        game.action("3+12".parse().unwrap()).unwrap();
+        assert_eq!(
+            Some(
+                "Moved 3 cards, 9 of hearts down to 7 of diamonds, from cascade 1 to cascade 2. \
+                 Cascade 1 is now empty."
+                    .to_string()
+            ),
+            DescriptionRenderer.describe_move(&before, game.tableau(), Language::English),
+        );
+    }
+
+    #[test]
+    fn svg_renderer() {
+        let svg = SvgRenderer.render(&tableau(), &View::default());
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(4, svg.matches("<g class=\"card\"").count());
+
+        // Six empty cascades, three empty cells and four empty foundations
+        assert_eq!(13, svg.matches("<rect class=\"empty\"").count());
+
+        // The ace of diamonds is highlighted for the foundation.
+        assert_eq!(1, svg.matches("stroke=\"#ffca28\"").count());
+        assert_eq!(svg, tableau().to_svg());
+    }
+
+    #[test]
+    fn ansi_renderer() {
+        let view = View {
+            options: DisplayOptions {
+                colours: ColourSupport::Plain,
+                ..DisplayOptions::default()
+            },
+            selected: None,
+        };
+
+        assert_eq!(
+            tableau().display(view.options).to_string(),
+            AnsiRenderer.render(&tableau(), &view),
+        );
+        assert!(AnsiRenderer
+            .render(&tableau(), &view)
+            .contains("A \u{2662}"));
+    }
+}
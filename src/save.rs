@@ -0,0 +1,306 @@
+use super::{Action, Card, Game, Single, Tableau, ToCoordinate, UndoMode};
+use std::time::{Duration, Instant};
+
+/// The first line of every saved game.
+const HEADER: &str = "# RefCell saved game";
+
+/// Every pile in the order they're written, which is the order they appear on screen.
+fn piles() -> impl Iterator<Item = ToCoordinate> {
+    (0..4)
+        .map(ToCoordinate::Cell)
+        .chain((0..4).map(ToCoordinate::Foundation))
+        .chain((0..8).map(ToCoordinate::Cascade))
+}
+
+fn cards(tableau: &Tableau, pile: ToCoordinate) -> &[Card] {
+    match pile {
+        ToCoordinate::Cascade(n) => tableau.cascades[n as usize].cards(),
+        ToCoordinate::Cell(n) => tableau.cells[n as usize]
+            .peek()
+            .map_or(&[], std::slice::from_ref),
+        ToCoordinate::Foundation(n) => tableau.foundations[n as usize].cards(),
+    }
+}
+
+/// Write out a game as plain text: the variant, the undo mode if it isn't the default, the deal
+/// number if it has one, the deal with each pile listed from the bottom up, the number of undos if
+/// there were any, and the moves that have been played since, along with how many seconds after
+/// the first move each was played.
+///
+/// ```text
+/// # RefCell saved game
+/// variant: freecell
+/// undo: 3
+/// deal: 11982
+/// a:
+/// ...
+/// 1: JD KD 2S 4C 3S 6D 6S
+/// ...
+/// undos: 2
+/// times: 0.000 4.250
+/// moves: 3a 15+78
+/// ```
+pub fn save(game: &Game) -> String {
+    let start = &game.history()[0];
+    let mut lines = vec![HEADER.to_string(), format!("variant: {}", start.variant)];
+    if game.undo_mode() != UndoMode::default() {
+        lines.push(format!("undo: {}", game.undo_mode()));
+    }
+    lines.extend(game.number().map(|number| format!("deal: {}", number)));
+
+    lines.extend(piles().map(|pile| {
+        let codes: Vec<String> = cards(start, pile)
+            .iter()
+            .map(|card| {
+                format!(
+                    " {}{}",
+                    card.get_rank().as_char(),
+                    card.get_suit().as_letter()
+                )
+            })
+            .collect();
+        format!("{}:{}", pile, codes.concat())
+    }));
+
+    if game.undos() > 0 {
+        lines.push(format!("undos: {}", game.undos()));
+    }
+
+    if !game.times().is_empty() {
+        let times: Vec<String> = game
+            .times()
+            .iter()
+            .map(|time| format!("{:.3}", time.as_secs_f64()))
+            .collect();
+        lines.push(format!("times: {}", times.join(" ")));
+    }
+
+    let moves: Vec<String> = game.moves().iter().map(Action::to_string).collect();
+    lines.push(format!("moves: {}", moves.join(" ")).trim_end().to_string());
+
+    lines.join("\n") + "\n"
+}
+
+/// Read back a game written by `save`, replaying its moves so that undo works as usual.
+pub fn load(input: &str) -> Result<Game, &'static str> {
+    let mut lines = input.lines().map(str::trim);
+
+    if lines.next() != Some(HEADER) {
+        return Err("That isn't a saved game.");
+    }
+
+    let mut tableau = Tableau::empty();
+    let mut number = None;
+    let mut undo_mode = UndoMode::default();
+    let mut undos = 0;
+    let mut times = None;
+    let mut moves = Vec::new();
+
+    for line in lines.filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let (key, value) = line
+            .split_once(':')
+            .ok_or("The saved game has a line that can't be read.")?;
+        let (key, value) = (key.trim(), value.split_whitespace());
+
+        match key {
+            "variant" => tableau.variant = value.collect::<String>().parse()?,
+            "deal" => {
+                number = Some(
+                    value
+                        .collect::<String>()
+                        .parse()
+                        .map_err(|_| "The saved game has a line that can't be read.")?,
+                )
+            }
+            "undo" => undo_mode = value.collect::<String>().parse()?,
+            "undos" => {
+                undos = value
+                    .collect::<String>()
+                    .parse()
+                    .map_err(|_| "The saved game has a line that can't be read.")?
+            }
+            "times" => {
+                times = Some(
+                    value
+                        .map(|time| time.parse().map(Duration::from_secs_f64))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| "The saved game has a line that can't be read.")?,
+                )
+            }
+            "moves" => {
+                for action in value {
+                    moves.push(action.parse::<Action>()?);
+                }
+            }
+            _ => {
+                let pile = key
+                    .parse()
+                    .map_err(|_| "The saved game has a line that can't be read.")?;
+
+                for card in value {
+                    let card: Card = card.parse()?;
+                    match pile {
+                        ToCoordinate::Cascade(n) => tableau.cascades[n as usize].push(card),
+                        ToCoordinate::Cell(n) => tableau.cells[n as usize]
+                            .try_push(card)
+                            .map_err(|(_, message)| message)?,
+                        ToCoordinate::Foundation(n) => tableau.foundations[n as usize]
+                            .try_push(card)
+                            .map_err(|(_, message)| message)?,
+                    }
+                }
+            }
+        }
+    }
+
+    let mut dealt: Vec<&Card> = piles().flat_map(|pile| cards(&tableau, pile)).collect();
+    let count = dealt.len();
+    dealt.sort_by_key(|card| (card.get_suit().as_letter(), card.get_rank()));
+    dealt.dedup();
+    if count != 52 || dealt.len() != 52 {
+        return Err("The saved game doesn't have exactly one of each card.");
+    }
+
+    let mut game = Game {
+        number,
+        ..Game::new(tableau)
+    };
+    for action in moves {
+        game.action(action)
+            .map_err(|_| "The saved game has a move that can't be played.")?;
+    }
+
+    game.undo_mode = undo_mode;
+    game.undos = undos;
+
+    // Carry on the clock from where it was when the game was saved.
+    if let Some(times) = times {
+        if times.len() != game.times.len() {
+            return Err("The saved game has a different number of times and moves.");
+        }
+        let last = times.last().copied().unwrap_or_default();
+        game.started = Some(
+            Instant::now()
+                .checked_sub(last)
+                .unwrap_or_else(Instant::now),
+        );
+        game.times = times;
+    }
+
+    Ok(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Deck, Variant};
+
+    #[test]
+    fn round_trip() {
+        let mut game = Game::deal_variant(Deck::numbered(1), Variant::BakersGame);
+        game.action("3a".parse().unwrap()).unwrap();
+        game.action("2b".parse().unwrap()).unwrap();
+
+        let saved = save(&game);
+        assert!(saved.starts_with("# RefCell saved game\nvariant: bakers-game\na:\n"));
+        assert!(saved.contains("\n1: JD KD 2S 4C 3S 6D 6S\n"));
+        assert!(saved.contains("\ntimes: 0.000 0.000\nmoves: 3a 2b\n"));
+
+        let loaded = load(&saved).unwrap();
+        assert_eq!(Variant::BakersGame, loaded.tableau().variant);
+        assert_eq!(game.moves(), loaded.moves());
+        assert_eq!(saved, save(&loaded));
+    }
+
+    #[test]
+    fn deal_number() {
+        let game = Game::deal_numbered(11982, Variant::FreeCell);
+
+        let saved = save(&game);
+        assert!(saved.starts_with("# RefCell saved game\nvariant: freecell\ndeal: 11982\na:\n"));
+        assert_eq!(Some(11982), load(&saved).unwrap().number());
+        assert_eq!(
+            None,
+            load(&save(&Game::deal(Deck::numbered(1))))
+                .unwrap()
+                .number()
+        );
+    }
+
+    #[test]
+    fn undos() {
+        let mut game = Game::deal(Deck::numbered(1));
+        game.action("3a".parse().unwrap()).unwrap();
+        game.action(Action::Undo).unwrap();
+        game.action("3b".parse().unwrap()).unwrap();
+
+        let saved = save(&game);
+        assert!(saved.ends_with("\nundos: 1\ntimes: 0.000\nmoves: 3b\n"));
+        assert_eq!(1, load(&saved).unwrap().undos());
+    }
+
+    #[test]
+    fn undo_mode() {
+        let game = Game::deal_numbered(1, Variant::FreeCell).with_undo_mode(UndoMode::Off);
+
+        let saved = save(&game);
+        assert!(saved.starts_with("# RefCell saved game\nvariant: freecell\nundo: off\ndeal: 1\n"));
+        assert_eq!(UndoMode::Off, load(&saved).unwrap().undo_mode());
+        assert_eq!(
+            Err("Unknown undo mode."),
+            load(&saved.replace("undo: off", "undo: never")).map(|_| ()),
+        );
+    }
+
+    #[test]
+    fn times() {
+        let saved = save(&Game::deal(Deck::numbered(1)))
+            .replace("moves:", "times: 0.000 61.250\nmoves: 3a 2b");
+        let game = load(&saved).unwrap();
+
+        assert_eq!(
+            [Duration::ZERO, Duration::from_millis(61_250)],
+            game.times()
+        );
+        assert!(game.elapsed() >= Duration::from_millis(61_250));
+        assert!(save(&game).contains("\ntimes: 0.000 61.250\n"));
+    }
+
+    #[test]
+    fn no_moves() {
+        let saved = save(&Game::deal(Deck::numbered(1)));
+
+        assert!(saved.ends_with("\nmoves:\n"));
+        assert_eq!(0, load(&saved).unwrap().moves().len());
+    }
+
+    #[test]
+    fn invalid() {
+        let saved = save(&Game::deal(Deck::numbered(1)));
+
+        assert_eq!(
+            Err("That isn't a saved game."),
+            load("variant: freecell").map(|_| ())
+        );
+        assert_eq!(
+            Err("The saved game doesn't have exactly one of each card."),
+            load(&saved.replace(" JD ", " JH ")).map(|_| ()),
+        );
+        assert_eq!(
+            Err("The saved game has a move that can't be played."),
+            load(&saved.replace("moves:", "moves: 1w")).map(|_| ()),
+        );
+        assert_eq!(
+            Err("The saved game has a line that can't be read."),
+            load(&saved.replace("variant:", "variant")).map(|_| ()),
+        );
+        assert_eq!(
+            Err("The saved game has a different number of times and moves."),
+            load(&saved.replace("moves:", "times: 1.0\nmoves:")).map(|_| ()),
+        );
+        assert_eq!(
+            Err("Unknown variant."),
+            load(&saved.replace("freecell", "spider")).map(|_| ()),
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use super::{config_dir, Action, Game, Single, ToCoordinate};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How many points a game is worth, worked out from its moves. Every card played to a foundation
+/// earns points, and a won game earns a bonus for each free cell it never needed, while undos and
+/// the time taken cost points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scoring {
+    /// Points for each card played to a foundation.
+    pub foundation: i64,
+
+    /// Points taken off for each undo, unless the game's undo mode makes them free.
+    pub undo: i64,
+
+    /// Points taken off for each minute of play, counted by the second.
+    pub minute: i64,
+
+    /// Points for each free cell that was never needed, once the game is won.
+    pub free_cell: i64,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            foundation: 10,
+            undo: 10,
+            minute: 6,
+            free_cell: 50,
+        }
+    }
+}
+
+impl Scoring {
+    /// Where the scoring config file is read from: `scoring.conf` in the config directory.
+    pub fn config_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("scoring.conf"))
+    }
+
+    /// The scoring set in the config file, or the default if there isn't one. The file consists of
+    /// `key = value` lines, with a whole number of points for any of `foundation`, `undo`, `minute`
+    /// and `free_cell`. Anything left out keeps its default.
+    pub fn load() -> io::Result<Self> {
+        let path = match Self::config_path() {
+            Some(path) => path,
+            None => return Ok(Self::default()),
+        };
+
+        match fs::read_to_string(path) {
+            Ok(config) => config
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The score for a game that has taken `time` so far. Once the game is won, the cards still on
+    /// the board count as played to the foundations, since there's nothing left to stop them.
+    pub fn score(&self, game: &Game, time: Duration) -> i64 {
+        let mut played = game
+            .moves()
+            .iter()
+            .filter(|action| {
+                matches!(
+                    action,
+                    Action::MoveCard {
+                        to: ToCoordinate::Foundation(_),
+                        ..
+                    }
+                )
+            })
+            .count();
+
+        let mut bonus = 0;
+        if game.is_won() {
+            let foundations = &game.tableau().foundations;
+            played += 52 - foundations.iter().map(|f| f.cards().len()).sum::<usize>();
+
+            let most_cells_used = game
+                .history()
+                .iter()
+                .map(|tableau| tableau.cells.iter().filter(|cell| !cell.is_empty()).count())
+                .max()
+                .unwrap_or(0);
+            bonus = (4 - most_cells_used) as i64 * self.free_cell;
+        }
+
+        let undos = if game.undo_mode().is_scored() {
+            game.undos()
+        } else {
+            0
+        };
+
+        played as i64 * self.foundation
+            - undos as i64 * self.undo
+            - time.as_secs() as i64 * self.minute / 60
+            + bonus
+    }
+}
+
+impl FromStr for Scoring {
+    type Err = &'static str;
+
+    fn from_str(config: &str) -> Result<Self, Self::Err> {
+        let mut scoring = Self::default();
+
+        for line in config.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = "The scoring config has a line that can't be read.";
+            let (key, value) = line.split_once('=').ok_or(error)?;
+            let points = value.trim().parse().map_err(|_| error)?;
+
+            match key.trim() {
+                "foundation" => scoring.foundation = points,
+                "undo" => scoring.undo = points,
+                "minute" => scoring.minute = points,
+                "free_cell" => scoring.free_cell = points,
+                _ => return Err("The scoring config has a setting that doesn't exist."),
+            }
+        }
+
+        Ok(scoring)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Deck, UndoMode};
+
+    #[test]
+    fn score() {
+        let scoring = Scoring::default();
+        let mut game = Game::deal(Deck::numbered(1));
+        assert_eq!(0, scoring.score(&game, Duration::ZERO));
+
+        // Deal #1 has the aces of clubs and spades in the sixth cascade, under the 2 of clubs and
+        // the 3 of diamonds.
+        for action in ["6a", "6b", "6w", "6x", "u"] {
+            game.action(action.parse().unwrap()).unwrap();
+        }
+        assert_eq!(1, game.undos());
+        assert_eq!(10 - 10, scoring.score(&game, Duration::ZERO));
+        assert_eq!(-7, scoring.score(&game, Duration::from_secs(75)));
+
+        let game = game.with_undo_mode(UndoMode::Free);
+        assert_eq!(10, scoring.score(&game, Duration::ZERO));
+    }
+
+    #[test]
+    fn won() {
+        let game = Game::load(
+            "# RefCell saved game
+            variant: freecell
+            w: AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC
+            x: AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD
+            y: AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH
+            z: AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS
+            1: QS KC
+            2: KD
+            3: KH
+            4: KS
+            moves: 1a",
+        )
+        .unwrap();
+
+        // Five cards to go, and one free cell used.
+        assert!(game.is_won());
+        assert_eq!(
+            5 * 10 + 3 * 50,
+            Scoring::default().score(&game, Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn config() {
+        let scoring: Scoring = "# Hard\nundo = 50\nminute=0\n".parse().unwrap();
+        assert_eq!(
+            Scoring {
+                undo: 50,
+                minute: 0,
+                ..Scoring::default()
+            },
+            scoring
+        );
+
+        assert_eq!(
+            Err("The scoring config has a line that can't be read."),
+            "undo: 50".parse::<Scoring>()
+        );
+        assert_eq!(
+            Err("The scoring config has a setting that doesn't exist."),
+            "redo = 5".parse::<Scoring>()
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use super::{data_dir, Language, UndoMode};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The first line of the statistics file.
+const HEADER: &str = "# RefCell statistics";
+
+/// How a game came to an end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    /// The game was won, in this many moves and this long, for this score.
+    Won {
+        moves: usize,
+        time: Duration,
+        score: i64,
+    },
+
+    /// The game was given up for a new one, or restarted, with moves played and before it was won.
+    Abandoned,
+}
+
+/// A player's record over every game they've finished in one undo mode, kept in the data directory
+/// and shared by all the frontends.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub won: u32,
+    pub abandoned: u32,
+
+    /// Games won since the last one that was abandoned.
+    pub streak: u32,
+    pub best_streak: u32,
+
+    /// The moves, time taken and score over every win, for working out the averages.
+    pub win_moves: u64,
+    pub win_time: Duration,
+    pub win_score: i64,
+
+    /// The highest score for a win, if there's been one.
+    pub best_score: Option<i64>,
+}
+
+impl Stats {
+    /// Where the statistics for an undo mode are kept, if there's anywhere to keep them. The
+    /// default mode keeps the `stats` file it always had, and each other mode has its own.
+    pub fn path(mode: UndoMode) -> Option<PathBuf> {
+        let name = match mode {
+            UndoMode::Costly => "stats".to_string(),
+            mode => format!("stats-undo-{}", mode),
+        };
+        data_dir().map(|dir| dir.join(name))
+    }
+
+    /// The statistics so far for an undo mode, which are all zero if there are none yet.
+    pub fn load(mode: UndoMode) -> io::Result<Self> {
+        let path = match Self::path(mode) {
+            Some(path) => path,
+            None => return Ok(Self::default()),
+        };
+
+        match fs::read_to_string(path) {
+            Ok(stats) => stats
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Add a game to the statistics on disk. The file is read again first, so that games finished
+    /// in another frontend in the meantime aren't lost.
+    pub fn record(mode: UndoMode, outcome: Outcome) -> io::Result<Self> {
+        let mut stats = Self::load(mode)?;
+        stats.add(outcome);
+
+        if let Some(path) = Self::path(mode) {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, stats.to_string())?;
+        }
+
+        Ok(stats)
+    }
+
+    /// Add a game to these statistics.
+    pub fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Won { moves, time, score } => {
+                self.won += 1;
+                self.streak += 1;
+                self.best_streak = self.best_streak.max(self.streak);
+                self.win_moves += moves as u64;
+                self.win_time += time;
+                self.win_score += score;
+                self.best_score = Some(self.best_score.map_or(score, |best| best.max(score)));
+            }
+            Outcome::Abandoned => {
+                self.abandoned += 1;
+                self.streak = 0;
+            }
+        }
+    }
+
+    pub fn played(&self) -> u32 {
+        self.won + self.abandoned
+    }
+
+    pub fn average_moves(&self) -> Option<f64> {
+        (self.won > 0).then(|| self.win_moves as f64 / f64::from(self.won))
+    }
+
+    pub fn average_time(&self) -> Option<Duration> {
+        (self.won > 0).then(|| self.win_time / self.won)
+    }
+
+    pub fn average_score(&self) -> Option<i64> {
+        (self.won > 0).then(|| self.win_score / i64::from(self.won))
+    }
+
+    /// The statistics as lines of text, in the player's language.
+    pub fn describe(&self, language: Language) -> Vec<String> {
+        let none = language.tr("none yet");
+        let percent_won = match self.played() {
+            0 => 0,
+            played => (self.won * 100 + played / 2) / played,
+        };
+        let average_moves = self
+            .average_moves()
+            .map_or(none.to_string(), |moves| format!("{:.1}", moves));
+        let average_time = self.average_time().map_or(none.to_string(), |time| {
+            let secs = time.as_secs();
+            format!("{}:{:02}", secs / 60, secs % 60)
+        });
+        let best_score = self
+            .best_score
+            .map_or(none.to_string(), |score| score.to_string());
+        let average_score = self
+            .average_score()
+            .map_or(none.to_string(), |score| score.to_string());
+
+        vec![
+            language.format("Games played: {}", &[&self.played()]),
+            language.format("Won: {} ({}%)", &[&self.won, &percent_won]),
+            language.format("Abandoned: {}", &[&self.abandoned]),
+            language.format("Current streak: {}", &[&self.streak]),
+            language.format("Best streak: {}", &[&self.best_streak]),
+            language.format("Average moves per win: {}", &[&average_moves]),
+            language.format("Average time per win: {}", &[&average_time]),
+            language.format("Best score: {}", &[&best_score]),
+            language.format("Average score per win: {}", &[&average_score]),
+        ]
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "won: {}", self.won)?;
+        writeln!(f, "abandoned: {}", self.abandoned)?;
+        writeln!(f, "streak: {}", self.streak)?;
+        writeln!(f, "best streak: {}", self.best_streak)?;
+        writeln!(f, "win moves: {}", self.win_moves)?;
+        writeln!(f, "win seconds: {}", self.win_time.as_secs())?;
+        writeln!(f, "win score: {}", self.win_score)?;
+        match self.best_score {
+            Some(score) => writeln!(f, "best score: {}", score),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Stats {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim);
+        if lines.next() != Some(HEADER) {
+            return Err("That isn't a statistics file.");
+        }
+
+        let mut stats = Self::default();
+        for line in lines.filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let error = "The statistics file has a line that can't be read.";
+            let (key, value) = line.split_once(':').ok_or(error)?;
+            let value = value.trim();
+
+            match key.trim() {
+                "won" => stats.won = value.parse().map_err(|_| error)?,
+                "abandoned" => stats.abandoned = value.parse().map_err(|_| error)?,
+                "streak" => stats.streak = value.parse().map_err(|_| error)?,
+                "best streak" => stats.best_streak = value.parse().map_err(|_| error)?,
+                "win moves" => stats.win_moves = value.parse().map_err(|_| error)?,
+                "win seconds" => {
+                    stats.win_time = Duration::from_secs(value.parse().map_err(|_| error)?)
+                }
+                "win score" => stats.win_score = value.parse().map_err(|_| error)?,
+                "best score" => stats.best_score = Some(value.parse().map_err(|_| error)?),
+                _ => return Err(error),
+            }
+        }
+
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn won(moves: usize, secs: u64, score: i64) -> Outcome {
+        Outcome::Won {
+            moves,
+            time: Duration::from_secs(secs),
+            score,
+        }
+    }
+
+    #[test]
+    fn streaks() {
+        let mut stats = Stats::default();
+        stats.add(won(80, 300, 500));
+        stats.add(won(100, 200, 700));
+        stats.add(Outcome::Abandoned);
+        stats.add(won(120, 400, 600));
+
+        assert_eq!(4, stats.played());
+        assert_eq!(1, stats.streak);
+        assert_eq!(2, stats.best_streak);
+        assert_eq!(Some(100.0), stats.average_moves());
+        assert_eq!(Some(Duration::from_secs(300)), stats.average_time());
+        assert_eq!(Some(700), stats.best_score);
+        assert_eq!(Some(600), stats.average_score());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut stats = Stats::default();
+        stats.add(won(95, 321, 640));
+        stats.add(Outcome::Abandoned);
+
+        assert_eq!(Ok(stats.clone()), stats.to_string().parse());
+        assert_eq!(
+            Err("That isn't a statistics file."),
+            "won: 1".parse::<Stats>()
+        );
+        assert_eq!(
+            Err("The statistics file has a line that can't be read."),
+            stats
+                .to_string()
+                .replace("won: 1", "won: one")
+                .parse::<Stats>()
+        );
+    }
+
+    #[test]
+    fn path() {
+        let stats = |mode| Stats::path(mode).map(|path| path.file_name().unwrap().to_owned());
+        if Stats::path(UndoMode::Costly).is_some() {
+            assert_eq!(Some("stats".into()), stats(UndoMode::Costly));
+            assert_eq!(Some("stats-undo-off".into()), stats(UndoMode::Off));
+            assert_eq!(Some("stats-undo-3".into()), stats(UndoMode::Limited(3)));
+        }
+    }
+
+    #[test]
+    fn describe() {
+        let mut stats = Stats::default();
+        assert_eq!(
+            "Average moves per win: none yet",
+            stats.describe(Language::English)[5]
+        );
+
+        stats.add(won(95, 321, 640));
+        stats.add(won(96, 300, 560));
+        stats.add(Outcome::Abandoned);
+        assert_eq!(
+            vec![
+                "Games played: 3",
+                "Won: 2 (67%)",
+                "Abandoned: 1",
+                "Current streak: 0",
+                "Best streak: 2",
+                "Average moves per win: 95.5",
+                "Average time per win: 5:10",
+                "Best score: 640",
+                "Average score per win: 600",
+            ],
+            stats.describe(Language::English)
+        );
+    }
+}
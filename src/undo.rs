@@ -0,0 +1,106 @@
+use super::Language;
+use std::fmt;
+use std::str;
+
+/// How much undoing a game allows. The mode is kept with the game, so that statistics and scores
+/// from different modes aren't mixed up. On the command line and in saved games, it's written as
+/// `free`, `costly`, `off`, or the number of undos allowed, where `0` is read as `off`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum UndoMode {
+    /// Undo as often as you like, without losing any points.
+    Free,
+
+    /// Undo as often as you like, but each one costs the points set by the scoring.
+    #[default]
+    Costly,
+
+    /// Undo up to this many times, each one costing points as in `Costly`.
+    Limited(u32),
+
+    /// No undoing at all.
+    Off,
+}
+
+impl UndoMode {
+    /// Whether undos are taken off the score.
+    pub fn is_scored(self) -> bool {
+        self != Self::Free
+    }
+
+    /// How many more undos are allowed after `used`, or `None` if there's no limit.
+    pub fn remaining(self, used: usize) -> Option<usize> {
+        match self {
+            Self::Free | Self::Costly => None,
+            Self::Limited(limit) => Some((limit as usize).saturating_sub(used)),
+            Self::Off => Some(0),
+        }
+    }
+
+    /// The mode in words, in the player's language.
+    pub fn describe(self, language: Language) -> String {
+        match self {
+            Self::Free => language.tr("Undos are free").to_string(),
+            Self::Costly => language.tr("Undos cost points").to_string(),
+            Self::Limited(1) => language.tr("Only 1 undo").to_string(),
+            Self::Limited(limit) => language.format("Only {} undos", &[&limit]),
+            Self::Off => language.tr("No undos").to_string(),
+        }
+    }
+}
+
+impl str::FromStr for UndoMode {
+    type Err = &'static str;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim() {
+            "free" => Ok(Self::Free),
+            "costly" => Ok(Self::Costly),
+            "off" => Ok(Self::Off),
+            limit => match limit.parse() {
+                Ok(0) => Ok(Self::Off),
+                Ok(limit) => Ok(Self::Limited(limit)),
+                Err(_) => Err("Unknown undo mode."),
+            },
+        }
+    }
+}
+
+impl fmt::Display for UndoMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Free => f.write_str("free"),
+            Self::Costly => f.write_str("costly"),
+            Self::Limited(limit) => write!(f, "{}", limit),
+            Self::Off => f.write_str("off"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        for mode in [
+            UndoMode::Free,
+            UndoMode::Costly,
+            UndoMode::Limited(3),
+            UndoMode::Off,
+        ] {
+            assert_eq!(Ok(mode), mode.to_string().parse());
+        }
+        assert_eq!(Ok(UndoMode::Off), "0".parse());
+        assert_eq!(Ok(UndoMode::Limited(1)), " 1 ".parse());
+        assert_eq!(Err("Unknown undo mode."), "some".parse::<UndoMode>());
+        assert_eq!(Err("Unknown undo mode."), "-1".parse::<UndoMode>());
+    }
+
+    #[test]
+    fn remaining() {
+        assert_eq!(None, UndoMode::Costly.remaining(10));
+        assert_eq!(Some(1), UndoMode::Limited(3).remaining(2));
+        assert_eq!(Some(0), UndoMode::Limited(3).remaining(4));
+        assert_eq!(Some(0), UndoMode::Off.remaining(0));
+    }
+}
@@ -0,0 +1,81 @@
+use super::Card;
+use std::fmt;
+use std::str;
+
+/// The rules being played. Every variant uses the same deal and layout, and differs only in how
+/// cards can be built on the cascades.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Variant {
+    /// Cascades build down in alternating colours.
+    #[default]
+    FreeCell,
+
+    /// The game FreeCell was adapted from, where cascades build down by suit.
+    BakersGame,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Self::FreeCell, Self::BakersGame];
+
+    /// The name used to choose the variant on the command line and in saved games.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::FreeCell => "freecell",
+            Self::BakersGame => "bakers-game",
+        }
+    }
+
+    /// Whether `card` can be placed on top of `onto` in a cascade.
+    pub fn is_legal(self, onto: &Card, card: &Card) -> bool {
+        match self {
+            Self::FreeCell => onto.is_legal(card),
+            Self::BakersGame => {
+                onto.get_suit() == card.get_suit()
+                    && onto.get_rank().try_decrement() == Some(card.get_rank())
+            }
+        }
+    }
+}
+
+impl str::FromStr for Variant {
+    type Err = &'static str;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|variant| variant.as_str() == input.trim())
+            .ok_or("Unknown variant.")
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rank, Suit};
+
+    #[test]
+    fn is_legal() {
+        let onto = Card::new(Rank::Five, Suit::Hearts);
+        let black = Card::new(Rank::Four, Suit::Spades);
+        let same_suit = Card::new(Rank::Four, Suit::Hearts);
+
+        assert!(Variant::FreeCell.is_legal(&onto, &black));
+        assert!(!Variant::FreeCell.is_legal(&onto, &same_suit));
+        assert!(!Variant::BakersGame.is_legal(&onto, &black));
+        assert!(Variant::BakersGame.is_legal(&onto, &same_suit));
+        assert!(!Variant::BakersGame.is_legal(&onto, &Card::new(Rank::Three, Suit::Hearts)));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Variant::FreeCell), "freecell".parse());
+        assert_eq!(Ok(Variant::BakersGame), " bakers-game\n".parse());
+        assert_eq!(Err("Unknown variant."), "spider".parse::<Variant>());
+    }
+}